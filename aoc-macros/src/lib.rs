@@ -0,0 +1,283 @@
+//! The `#[aoc]` attribute, registering a day crate's solvers.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::Parser;
+use syn::spanned::Spanned;
+use syn::{Error, FnArg, ItemFn, LitInt, LitStr, ReturnType};
+
+/// Registers a solver as one part of a day:
+///
+/// ```ignore
+/// #[aoc(day = 4, part = 1)]
+/// pub fn count_contained(pairs: &[(ElfRange, ElfRange)]) -> usize { .. }
+/// ```
+///
+/// The solver takes a reference to what the crate's `parse` returns, possibly
+/// dereferenced (`&[T]` for a `Vec<T>`), and returns anything `util::Answer`
+/// converts from. It is exposed as `PART1` or `PART2`, a `util::Part` whose
+/// `main` is the binary's entry point. Cargo still needs a file per binary,
+/// so `src/bin/one.rs` is only `fn main() { aoc4::PART1.main() }`. The crate
+/// must have a `util` dependency on `aoc-util` and a `NORMALIZE` const, and
+/// what `parse` returns must be `serde::Serialize`, for `aoc parse --dump`.
+///
+/// The runner's build script registers the day from the solvers at the root
+/// of its `lib.rs`, so a day needs nothing more than its `day{n}` feature.
+/// A day slower than `util::DEFAULT_TIME_BUDGET` allows sets its own, as a
+/// `TIME_BUDGET` const saying why it needs longer.
+///
+/// Alternative implementations of a part are registered as named variants,
+/// exposed as e.g. `PART1_SCAN` for `aoc compare` to check against the solution:
+///
+/// ```ignore
+/// #[aoc(day = 1, part = 1, variant = "scan")]
+/// ```
+#[proc_macro_attribute]
+pub fn aoc(args: TokenStream, item: TokenStream) -> TokenStream {
+    expand(args.into(), item.into())
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(args: TokenStream2, item: TokenStream2) -> syn::Result<TokenStream2> {
+    let mut day = None;
+    let mut part = None;
+    let mut variant = None;
+    let args_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("variant") {
+            let lit = meta.value()?.parse::<LitStr>()?;
+            let name = lit.value();
+            let valid = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-';
+            if name.is_empty() || !name.chars().all(valid) {
+                return Err(Error::new(
+                    lit.span(),
+                    "expected a variant name of lowercase letters, digits and `-`",
+                ));
+            }
+            variant = Some(name);
+            return Ok(());
+        }
+        let (value, range) = if meta.path.is_ident("day") {
+            (&mut day, 1..=25)
+        } else if meta.path.is_ident("part") {
+            (&mut part, 1..=2)
+        } else {
+            return Err(meta.error("expected `day`, `part` or `variant`"));
+        };
+        let lit = meta.value()?.parse::<LitInt>()?;
+        let n = lit.base10_parse::<u8>()?;
+        if !range.contains(&n) {
+            return Err(Error::new(
+                lit.span(),
+                format!("expected {} to {}", range.start(), range.end()),
+            ));
+        }
+        *value = Some(n);
+        Ok(())
+    });
+    args_parser.parse2(args)?;
+    let missing = |name| Error::new(Span::call_site(), format!("missing `{} = ..`", name));
+    let day = day.ok_or_else(|| missing("day"))?;
+    let part = part.ok_or_else(|| missing("part"))?;
+
+    let solver = syn::parse2::<ItemFn>(item)?;
+    let sig = &solver.sig;
+    let input = match sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(input)] => input,
+        _ => {
+            return Err(Error::new(
+                sig.inputs.span(),
+                "a solver takes one argument, a reference to the parsed input",
+            ))
+        }
+    };
+    if let ReturnType::Default = sig.output {
+        return Err(Error::new(sig.ident.span(), "a solver returns its answer"));
+    }
+
+    let name = &sig.ident;
+    let (constant, doc, variant) = match &variant {
+        None => (
+            format_ident!("PART{}", part),
+            format!("Part {} of day {}, solved by [`{}`].", part, day, name),
+            quote!(::std::option::Option::None),
+        ),
+        Some(variant) => (
+            format_ident!("PART{}_{}", part, variant.to_uppercase().replace('-', "_")),
+            format!(
+                "Variant `{}` of part {} of day {}, solved by [`{}`].",
+                variant, part, day, name
+            ),
+            quote!(::std::option::Option::Some(#variant)),
+        ),
+    };
+    // Bound before the call so the model's type comes from `parse`, letting
+    // the call deref it to the solver's argument. Spanned so a solver that
+    // does not fit the parsed input or returns something that is not an
+    // answer is reported on its own signature.
+    let parsed = quote_spanned!(input.ty.span()=> #name(parsed));
+    let answer = quote_spanned!(sig.output.span()=> ::util::Answer::from(#parsed));
+    let version = source_version();
+    Ok(quote! {
+        #solver
+
+        #[doc = #doc]
+        pub const #constant: ::util::Part = ::util::Part {
+            day: #day,
+            part: #part,
+            variant: #variant,
+            version: #version,
+            normalize: crate::NORMALIZE,
+            parse: |lines| ::std::result::Result::Ok(::std::boxed::Box::new(crate::parse(lines)?)),
+            solve: |model| {
+                let parsed = ::util::model_of(crate::parse, model);
+                #answer
+            },
+            dump: |model, format| {
+                let parsed = ::util::model_of(crate::parse, model);
+                ::util::dump(parsed, format)
+            },
+        };
+    })
+}
+
+/// Hash of the manifest and sources of the crate being compiled, together
+/// with `util::BUILD_VERSION`, worked out as the crate builds. Including the
+/// files also has Cargo rebuild the crate whenever one of them changes.
+fn source_version() -> TokenStream2 {
+    let mut files = vec![];
+    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
+        let manifest_dir = Path::new(&manifest_dir);
+        files.push(manifest_dir.join("Cargo.toml"));
+        source_files(&manifest_dir.join("src"), &mut files);
+        files.sort();
+        // Named relative to the crate, so its version doesn't depend on where it is
+        for file in &mut files {
+            *file = file
+                .strip_prefix(manifest_dir)
+                .unwrap_or(file)
+                .to_path_buf();
+        }
+        let writes = files.iter().filter_map(|file| {
+            let name = file.to_str()?;
+            let path = manifest_dir.join(file);
+            let path = path.to_str()?;
+            Some(quote!(.write_sized(#name.as_bytes()).write_sized(include_bytes!(#path))))
+        });
+        return quote! {
+            ::util::Fnv64::new() #(#writes)* .write_u64(::util::BUILD_VERSION).finish()
+        };
+    }
+    quote!(::util::BUILD_VERSION)
+}
+
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            source_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(args: TokenStream2, item: TokenStream2) -> String {
+        expand(args, item).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn expands() {
+        let expanded = expand(
+            quote!(day = 4, part = 2),
+            quote!(
+                fn count(pairs: &[u8]) -> usize {
+                    pairs.len()
+                }
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert!(expanded.contains("pub const PART2 : :: util :: Part"));
+        assert!(expanded.contains("day : 4u8"));
+        // Bound first, so the model's type comes from `parse`
+        assert!(expanded.contains("let parsed = :: util :: model_of (crate :: parse , model) ;"));
+        // Hashed from this crate's own files, as it is the one being built
+        assert!(expanded.contains("version : :: util :: Fnv64 :: new ()"));
+        assert!(expanded.contains(". write_sized (\"src/lib.rs\" . as_bytes ())"));
+        assert!(expanded.contains(". write_u64 (:: util :: BUILD_VERSION) . finish ()"));
+
+        let expanded = expand(
+            quote!(day = 1, part = 1, variant = "linear-scan"),
+            quote!(
+                fn scan(elves: &[u8]) -> usize {
+                    elves.len()
+                }
+            ),
+        )
+        .unwrap()
+        .to_string();
+        assert!(expanded.contains("pub const PART1_LINEAR_SCAN"));
+        assert!(expanded.contains("Some (\"linear-scan\")"));
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        let solver = quote!(
+            fn solve(input: &[u8]) -> usize {
+                0
+            }
+        );
+        assert_eq!(
+            error(quote!(day = 4), solver.clone()),
+            "missing `part = ..`"
+        );
+        assert_eq!(
+            error(quote!(day = 26, part = 1), solver.clone()),
+            "expected 1 to 25"
+        );
+        assert_eq!(
+            error(quote!(day = 4, part = 3), solver.clone()),
+            "expected 1 to 2"
+        );
+        assert_eq!(
+            error(quote!(day = 4, part = 1, year = 2022), solver.clone()),
+            "expected `day`, `part` or `variant`"
+        );
+        assert_eq!(
+            error(quote!(day = 4, part = 1, variant = "Fast"), solver),
+            "expected a variant name of lowercase letters, digits and `-`"
+        );
+        assert_eq!(
+            error(
+                quote!(day = 4, part = 1),
+                quote!(
+                    fn solve(a: &u8, b: &u8) -> usize {
+                        0
+                    }
+                )
+            ),
+            "a solver takes one argument, a reference to the parsed input"
+        );
+        assert_eq!(
+            error(
+                quote!(day = 4, part = 1),
+                quote!(
+                    fn solve(a: &u8) {}
+                )
+            ),
+            "a solver returns its answer"
+        );
+    }
+}
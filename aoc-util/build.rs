@@ -0,0 +1,65 @@
+//! Hashes what every solver's build depends on besides its own crate: this
+//! crate's sources, the locked versions of all dependencies and the compiler,
+//! into `BUILD_VERSION`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[allow(dead_code)]
+#[path = "src/hash.rs"]
+mod hash;
+
+use hash::Fnv64;
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let mut files = vec![manifest_dir.join("Cargo.toml")];
+    source_files(&manifest_dir.join("src"), &mut files);
+    files.sort();
+    // The workspace's, when built as part of one
+    files.extend(
+        manifest_dir
+            .ancestors()
+            .map(|dir| dir.join("Cargo.lock"))
+            .find(|path| path.is_file()),
+    );
+
+    let mut hasher = Fnv64::new();
+    for file in &files {
+        println!("cargo:rerun-if-changed={}", file.display());
+        let name = file.strip_prefix(&manifest_dir).unwrap_or(file);
+        hasher = hasher
+            .write_sized(name.to_string_lossy().as_bytes())
+            .write_sized(&fs::read(file).unwrap_or_default());
+    }
+    println!(
+        "cargo:rerun-if-changed={}",
+        manifest_dir.join("src").display()
+    );
+    let rustc = env::var_os("RUSTC").unwrap_or_else(|| "rustc".into());
+    let rustc_version = Command::new(rustc)
+        .arg("-vV")
+        .output()
+        .map(|output| output.stdout)
+        .unwrap_or_default();
+    hasher = hasher.write_sized(&rustc_version);
+
+    let out = PathBuf::from(env::var_os("OUT_DIR").unwrap()).join("build_version.rs");
+    fs::write(&out, format!("{:#018x}\n", hasher.finish()))
+        .unwrap_or_else(|e| panic!("{}: {}", out.display(), e));
+}
+
+fn source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+        if path.is_dir() {
+            source_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
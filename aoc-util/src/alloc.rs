@@ -0,0 +1,127 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// Global allocator that counts allocations on top of the system allocator.
+///
+/// A binary opts in by installing it, then turning counting on when wanted:
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: util::CountingAlloc = util::CountingAlloc;
+///
+/// util::CountingAlloc::enable();
+/// ```
+pub struct CountingAlloc;
+
+/// Allocations made while running a measured closure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    pub allocations: usize,
+    pub bytes_allocated: usize,
+    /// Highest number of bytes live at once, above what was live beforehand.
+    pub peak_live_bytes: usize,
+}
+
+impl CountingAlloc {
+    pub fn enable() {
+        ENABLED.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Runs `f`, returning its result along with the allocations it made.
+    /// Stats are all zero unless the allocator is installed and enabled.
+    pub fn measure<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+        let allocations = ALLOCATIONS.load(Ordering::SeqCst);
+        let bytes_allocated = BYTES_ALLOCATED.load(Ordering::SeqCst);
+        let live_bytes = LIVE_BYTES.load(Ordering::SeqCst);
+        PEAK_LIVE_BYTES.store(live_bytes, Ordering::SeqCst);
+
+        let result = f();
+
+        let stats = AllocStats {
+            allocations: ALLOCATIONS.load(Ordering::SeqCst) - allocations,
+            bytes_allocated: BYTES_ALLOCATED.load(Ordering::SeqCst) - bytes_allocated,
+            peak_live_bytes: PEAK_LIVE_BYTES.load(Ordering::SeqCst).saturating_sub(live_bytes),
+        };
+        (result, stats)
+    }
+
+    fn record_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        BYTES_ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(size: usize) {
+        // Allocations from before counting was enabled may be freed
+        let _ = LIVE_BYTES.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |live| {
+            Some(live.saturating_sub(size))
+        });
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() && Self::is_enabled() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() && Self::is_enabled() {
+            Self::record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        if Self::is_enabled() {
+            Self::record_dealloc(layout.size());
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() && Self::is_enabled() {
+            // Counted as freeing the old block and allocating the new one
+            Self::record_dealloc(layout.size());
+            Self::record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    #[test]
+    fn measure() {
+        CountingAlloc::enable();
+        let (len, stats) = CountingAlloc::measure(|| {
+            drop(vec![0u8; 4096]);
+            vec![0u8; 1024].len()
+        });
+        assert_eq!(len, 1024);
+        // Other tests may allocate concurrently, so only check lower bounds
+        assert!(stats.allocations >= 2);
+        assert!(stats.bytes_allocated >= 5120);
+        assert!(stats.peak_live_bytes >= 4096);
+    }
+}
@@ -0,0 +1,203 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::ParseError;
+
+/// A day's answer to one part of the puzzle.
+///
+/// Answers compare equal when their `Display` forms do, as the puzzle checks
+/// what is submitted, so an answer parsed from its stored form matches
+/// whatever type the solver returned it as.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Unsigned(u128),
+    Signed(i128),
+    String(String),
+    /// Multi-line answers, such as letters drawn on a grid of pixels.
+    Grid(Vec<String>),
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Unsigned(a), Answer::Unsigned(b)) => a == b,
+            (Answer::Signed(a), Answer::Signed(b)) => a == b,
+            (Answer::String(a), Answer::String(b)) => a == b,
+            (Answer::Grid(a), Answer::Grid(b)) => a == b,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Unsigned(n) => write!(f, "{}", n),
+            Answer::Signed(n) => write!(f, "{}", n),
+            Answer::String(s) => write!(f, "{}", s),
+            Answer::Grid(rows) => write!(f, "{}", rows.join("\n")),
+        }
+    }
+}
+
+/// Parses the stored form of an answer, i.e. its `Display` output.
+/// Multiple lines make a grid, otherwise an integer written the way it is
+/// displayed is parsed as one, and anything else, like `007`, is kept as a
+/// string so the answer displays as it was stored.
+impl FromStr for Answer {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim_end_matches(['\r', '\n']);
+        if s.is_empty() {
+            return Err(ParseError::new("answer is empty"));
+        }
+        if s.contains('\n') {
+            return Ok(Answer::Grid(
+                s.lines().map(|l| String::from(l.trim_end_matches('\r'))).collect(),
+            ));
+        }
+        let integer = s
+            .parse()
+            .map(Answer::Unsigned)
+            .or_else(|_| s.parse().map(Answer::Signed));
+        Ok(match integer {
+            Ok(n) if n.to_string() == s => n,
+            _ => Answer::String(String::from(s)),
+        })
+    }
+}
+
+/// Tagged with its type, e.g. `{"type": "grid", "value": ["#..#", ".##."]}`.
+/// Integers are numbers where they fit in 64 bits, and strings beyond, as
+/// most readers of JSON lose precision past that.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut answer = serializer.serialize_struct("Answer", 2)?;
+        match self {
+            Answer::Unsigned(n) => {
+                answer.serialize_field("type", "integer")?;
+                match u64::try_from(*n) {
+                    Ok(n) => answer.serialize_field("value", &n)?,
+                    Err(_) => answer.serialize_field("value", &n.to_string())?,
+                }
+            }
+            Answer::Signed(n) => {
+                answer.serialize_field("type", "integer")?;
+                match i64::try_from(*n) {
+                    Ok(n) => answer.serialize_field("value", &n)?,
+                    Err(_) => answer.serialize_field("value", &n.to_string())?,
+                }
+            }
+            Answer::String(s) => {
+                answer.serialize_field("type", "string")?;
+                answer.serialize_field("value", s)?;
+            }
+            Answer::Grid(rows) => {
+                answer.serialize_field("type", "grid")?;
+                answer.serialize_field("value", rows)?;
+            }
+        }
+        answer.end()
+    }
+}
+
+macro_rules! answer_from {
+    ($variant:ident: $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::$variant(n.into())
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(Unsigned: u8, u16, u32, u64, u128);
+answer_from!(Signed: i8, i16, i32, i64, i128);
+answer_from!(String: String, &str, char);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Unsigned(n as u128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Answer::Signed(n as i128)
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(rows: Vec<String>) -> Self {
+        Answer::Grid(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_display_forms() {
+        assert_eq!(Answer::from(45000usize), Answer::from(45000i64));
+        assert_eq!(Answer::from(157u64), Answer::Signed(157));
+        assert_eq!(Answer::from(7usize), Answer::from("7"));
+        assert_eq!(Answer::from(vec![String::from("ABC")]), Answer::from("ABC"));
+        assert_ne!(Answer::from(7usize), Answer::from("007"));
+        assert_ne!(Answer::from(-7i64), Answer::from(7u8));
+    }
+
+    #[test]
+    fn round_trips_stored_form() {
+        let answers = [
+            Answer::from(45000usize),
+            Answer::from(-3i64),
+            Answer::from(u128::from(u64::MAX) * 4),
+            Answer::from(u128::MAX),
+            Answer::from(i128::MIN),
+            Answer::from("CMZ"),
+            Answer::from("007"),
+            Answer::from("-5"),
+            Answer::from("+5"),
+            Answer::from(vec![String::from("#..#"), String::from(".##.")]),
+            Answer::from(vec![String::from("#..#")]),
+        ];
+        for answer in answers {
+            let stored = answer.to_string().parse::<Answer>().unwrap();
+            assert_eq!(stored, answer);
+            assert_eq!(stored.to_string(), answer.to_string());
+        }
+    }
+
+    #[test]
+    fn serializes_tagged() {
+        let json = |answer: Answer| serde_json::to_string(&answer).unwrap();
+        assert_eq!(json(Answer::from(-3i64)), r#"{"type":"integer","value":-3}"#);
+        assert_eq!(
+            json(Answer::from(u128::MAX)),
+            format!(r#"{{"type":"integer","value":"{}"}}"#, u128::MAX)
+        );
+        assert_eq!(json(Answer::from("007")), r#"{"type":"string","value":"007"}"#);
+        assert_eq!(
+            json(Answer::from(vec![String::from("#.")])),
+            r##"{"type":"grid","value":["#."]}"##
+        );
+    }
+
+    #[test]
+    fn parses_stored_types() {
+        assert!(matches!("12\n".parse(), Ok(Answer::Unsigned(12))));
+        assert!(matches!("-12".parse(), Ok(Answer::Signed(-12))));
+        assert!(matches!("MCD".parse(), Ok(Answer::String(_))));
+        assert!(matches!("007".parse(), Ok(Answer::String(_))));
+        assert!(matches!("-0".parse(), Ok(Answer::String(_))));
+        assert!("".parse::<Answer>().is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{Answer, Fnv64};
+
+/// Answers already worked out, stored one file per [`CacheKey`] in a directory.
+#[derive(Debug, Clone)]
+pub struct AnswerCache {
+    dir: PathBuf,
+}
+
+/// What an answer depends on: the puzzle, its input and the build of its solver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheKey {
+    pub day: u8,
+    pub part: u8,
+    /// Hash of the raw input, from [`input_hash`].
+    pub input: u64,
+    /// Version of the solver, as stamped on its `Part`.
+    pub solver: u64,
+}
+
+/// Hash of a raw input, for a [`CacheKey`].
+pub fn input_hash(input: &str) -> u64 {
+    Fnv64::new().write(input.as_bytes()).finish()
+}
+
+impl CacheKey {
+    fn file_name(&self) -> String {
+        format!(
+            "day{}-part{}-{:016x}-{:016x}",
+            self.day, self.part, self.input, self.solver
+        )
+    }
+}
+
+impl AnswerCache {
+    /// The cache in `dir`, which is created once an answer is stored.
+    pub fn new<P: AsRef<Path>>(dir: P) -> AnswerCache {
+        AnswerCache {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The cached answer for `key`. Unreadable entries count as missing.
+    pub fn get(&self, key: &CacheKey) -> Option<Answer> {
+        let stored = fs::read_to_string(self.dir.join(key.file_name())).ok()?;
+        stored.parse().ok()
+    }
+
+    /// Caches the answer for `key`.
+    pub fn set(&self, key: &CacheKey, answer: &Answer) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(key.file_name()), answer.to_string())
+    }
+
+    /// Removes the cached answers of `day`, or of every day, returning how many
+    /// there were.
+    pub fn clear(&self, day: Option<u8>) -> io::Result<usize> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+        let prefix = day.map(|day| format!("day{}-", day));
+        let mut removed = 0;
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name();
+            let matches = match (&prefix, name.to_str()) {
+                (Some(prefix), Some(name)) => name.starts_with(prefix.as_str()),
+                (None, _) => true,
+                (Some(_), None) => false,
+            };
+            if matches {
+                fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_set_clear() {
+        let dir = std::env::temp_dir().join(format!("aoc-answer-cache-{}", std::process::id()));
+        let cache = AnswerCache::new(&dir);
+        let key = |day, part| CacheKey {
+            day,
+            part,
+            input: input_hash("1\n2\n"),
+            solver: 7,
+        };
+        assert_eq!(cache.get(&key(1, 1)), None);
+        assert_eq!(cache.clear(None).unwrap(), 0);
+
+        cache.set(&key(1, 1), &Answer::Unsigned(3)).unwrap();
+        cache
+            .set(&key(1, 2), &Answer::String(String::from("CMZ")))
+            .unwrap();
+        cache.set(&key(12, 1), &Answer::Signed(-1)).unwrap();
+        assert_eq!(cache.get(&key(1, 1)), Some(Answer::Unsigned(3)));
+        assert_eq!(
+            cache.get(&key(1, 2)),
+            Some(Answer::String(String::from("CMZ")))
+        );
+        let rebuilt = CacheKey {
+            solver: 8,
+            ..key(1, 1)
+        };
+        assert_eq!(cache.get(&rebuilt), None);
+
+        // Only day 1, not day 12
+        assert_eq!(cache.clear(Some(1)).unwrap(), 2);
+        assert_eq!(cache.get(&key(1, 1)), None);
+        assert_eq!(cache.get(&key(12, 1)), Some(Answer::Signed(-1)));
+        assert_eq!(cache.clear(None).unwrap(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
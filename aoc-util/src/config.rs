@@ -0,0 +1,658 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+/// Name of the workspace configuration file, looked for in the current
+/// directory and its ancestors.
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// How commands print their results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!(
+                "unknown output format {:?}, expected text or json",
+                s
+            )),
+        }
+    }
+}
+
+/// One layer of settings. Layers are applied in order: `aoc.toml`, then
+/// `AOC_*` environment variables, then command line flags.
+///
+/// Path patterns may use `{year}`, `{day}` and `{day:02}` (zero-padded).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Settings {
+    pub year: Option<u16>,
+    /// Directory of each day's crate.
+    pub day_dir: Option<String>,
+    /// Input file of each day.
+    pub input_path: Option<String>,
+    /// File recording each year's known answers.
+    pub answers_path: Option<String>,
+    /// Crate copied by `aoc new` for each new day.
+    pub template_dir: Option<String>,
+    /// File holding the adventofcode.com session cookie.
+    pub session_file: Option<String>,
+    pub cache_dir: Option<String>,
+    /// Directory `aoc run` loads solution plugins from.
+    pub plugin_dir: Option<String>,
+    pub output_format: Option<OutputFormat>,
+    pub base_url: Option<String>,
+}
+
+/// Settings that can differ for a single year, such as a layout that
+/// predates the workspace's current one.
+#[derive(Debug, Clone, Default)]
+pub struct YearSettings {
+    pub day_dir: Option<String>,
+    pub input_path: Option<String>,
+    pub answers_path: Option<String>,
+    pub days: BTreeMap<u8, DaySettings>,
+}
+
+/// Settings that can differ for a single day.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DaySettings {
+    pub input_path: Option<String>,
+    pub output_format: Option<OutputFormat>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    #[serde(flatten)]
+    settings: Settings,
+    #[serde(default)]
+    years: BTreeMap<String, RawYearSettings>,
+}
+
+/// [`YearSettings`] as written, before the day keys are checked.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawYearSettings {
+    day_dir: Option<String>,
+    input_path: Option<String>,
+    answers_path: Option<String>,
+    #[serde(default)]
+    day: BTreeMap<String, DaySettings>,
+}
+
+/// Error loading or validating the configuration, naming where the bad value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub source: String,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub(crate) fn new<S: Into<String>, M: Into<String>>(source: S, message: M) -> Self {
+        ConfigError {
+            source: source.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.source, self.message)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Workspace configuration with every layer applied.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Directory relative paths are resolved against: the one holding `aoc.toml`.
+    pub root: PathBuf,
+    /// Year commands act on unless told otherwise.
+    pub year: u16,
+    pub day_dir: String,
+    pub input_path: String,
+    pub answers_path: String,
+    pub template_dir: PathBuf,
+    pub session_file: PathBuf,
+    pub cache_dir: PathBuf,
+    pub plugin_dir: PathBuf,
+    pub output_format: OutputFormat,
+    pub base_url: String,
+    pub years: BTreeMap<u16, YearSettings>,
+    /// Session cookie from `AOC_SESSION`, used instead of `session_file`.
+    session: Option<Secret>,
+}
+
+/// A value left out of `Debug` output, as the session cookie is.
+#[derive(Clone)]
+struct Secret(String);
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret(..)")
+    }
+}
+
+impl Config {
+    /// Loads `aoc.toml` from the current directory or its nearest ancestor that
+    /// has one, then applies the environment and `overrides` on top.
+    pub fn load(overrides: &Settings) -> Result<Config, ConfigError> {
+        let cwd = std::env::current_dir()
+            .map_err(|e| ConfigError::new("current directory", e.to_string()))?;
+        let path = cwd
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| path.is_file());
+        Config::load_from(
+            path.as_deref(),
+            &cwd,
+            |var| std::env::var(var).ok(),
+            overrides,
+        )
+    }
+
+    /// Loads the config file at `path` if given, otherwise the defaults rooted at
+    /// `default_root`, then applies variables from `env` and `overrides`.
+    pub fn load_from(
+        path: Option<&Path>,
+        default_root: &Path,
+        env: impl Fn(&str) -> Option<String>,
+        overrides: &Settings,
+    ) -> Result<Config, ConfigError> {
+        let (root, file) = match path {
+            Some(path) => {
+                let source = path.display().to_string();
+                let text = fs::read_to_string(path)
+                    .map_err(|e| ConfigError::new(&source, e.to_string()))?;
+                let file = toml::from_str::<ConfigFile>(&text)
+                    .map_err(|e| ConfigError::new(&source, e.to_string().trim_end()))?;
+                let root = path
+                    .parent()
+                    .map_or_else(|| PathBuf::from("."), Path::to_path_buf);
+                (root, file)
+            }
+            None => (default_root.to_path_buf(), ConfigFile::default()),
+        };
+        let source = path.map_or_else(|| String::from(CONFIG_FILE), |p| p.display().to_string());
+
+        let mut years = BTreeMap::new();
+        for (key, raw) in file.years {
+            let year = key
+                .parse::<u16>()
+                .ok()
+                .filter(|year| *year >= 2015)
+                .ok_or_else(|| {
+                    ConfigError::new(
+                        &source,
+                        format!("[years.{}]: Advent of Code years start at 2015", key),
+                    )
+                })?;
+            years.insert(year, year_settings(&source, &key, raw)?);
+        }
+
+        let mut settings = file.settings;
+        check_settings(&source, &settings)?;
+        settings.merge(env_settings(&env)?);
+        check_settings("command line", overrides)?;
+        settings.merge(overrides.clone());
+
+        let resolve = |path: String| {
+            let path = match path.strip_prefix("~/") {
+                Some(rest) => env("HOME").map_or_else(
+                    || PathBuf::from(&path),
+                    |home| PathBuf::from(home).join(rest),
+                ),
+                None => PathBuf::from(path),
+            };
+            root.join(path)
+        };
+
+        Ok(Config {
+            year: settings.year.unwrap_or(2022),
+            day_dir: settings
+                .day_dir
+                .unwrap_or_else(|| String::from("y{year}/day{day:02}")),
+            input_path: settings
+                .input_path
+                .unwrap_or_else(|| String::from("y{year}/day{day:02}/input.txt")),
+            answers_path: settings
+                .answers_path
+                .unwrap_or_else(|| String::from("answers/{year}.toml")),
+            template_dir: resolve(
+                settings
+                    .template_dir
+                    .unwrap_or_else(|| String::from("template")),
+            ),
+            session_file: resolve(
+                settings
+                    .session_file
+                    .unwrap_or_else(|| String::from(".session")),
+            ),
+            cache_dir: resolve(
+                settings
+                    .cache_dir
+                    .unwrap_or_else(|| String::from(".aoc-cache")),
+            ),
+            plugin_dir: resolve(
+                settings
+                    .plugin_dir
+                    .unwrap_or_else(|| String::from("plugins")),
+            ),
+            output_format: settings.output_format.unwrap_or(OutputFormat::Text),
+            base_url: settings
+                .base_url
+                .unwrap_or_else(|| String::from("https://adventofcode.com"))
+                .trim_end_matches('/')
+                .to_string(),
+            years,
+            root,
+            session: env("AOC_SESSION").map(Secret),
+        })
+    }
+
+    /// Directory of the day's crate.
+    pub fn day_dir(&self, year: u16, day: u8) -> PathBuf {
+        let year_settings = self.years.get(&year);
+        let pattern = year_settings
+            .and_then(|y| y.day_dir.as_deref())
+            .unwrap_or(&self.day_dir);
+        self.root.join(fill(pattern, year, day))
+    }
+
+    /// Name of the day's crate: its directory below the root, with `_` between
+    /// the parts, so `y2023/day01` is `y2023_day01` and 2022's `aoc5` is `aoc5`.
+    pub fn day_crate(&self, year: u16, day: u8) -> Result<String, ConfigError> {
+        let dir = self.day_dir(year, day);
+        let parts = dir
+            .strip_prefix(&self.root)
+            .map_err(|_| ConfigError::new(dir.display().to_string(), "is outside the workspace"))?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        Ok(parts.join("_"))
+    }
+
+    /// Input file for the day, taking day and year overrides into account.
+    pub fn input_path(&self, year: u16, day: u8) -> PathBuf {
+        let year_settings = self.years.get(&year);
+        let pattern = year_settings
+            .and_then(|y| y.days.get(&day))
+            .and_then(|d| d.input_path.as_deref())
+            .or_else(|| year_settings.and_then(|y| y.input_path.as_deref()))
+            .unwrap_or(&self.input_path);
+        self.root.join(fill(pattern, year, day))
+    }
+
+    /// File recording the year's known answers.
+    pub fn answers_path(&self, year: u16) -> PathBuf {
+        let pattern = self
+            .years
+            .get(&year)
+            .and_then(|y| y.answers_path.as_deref())
+            .unwrap_or(&self.answers_path);
+        self.root.join(pattern.replace("{year}", &year.to_string()))
+    }
+
+    /// Cache directory of the year.
+    pub fn year_cache_dir(&self, year: u16) -> PathBuf {
+        self.cache_dir.join(year.to_string())
+    }
+
+    /// Directory of the year's [`crate::AnswerCache`].
+    pub fn answer_cache_dir(&self, year: u16) -> PathBuf {
+        self.year_cache_dir(year).join("answers")
+    }
+
+    /// Output format for the day, taking its override into account.
+    pub fn output_format(&self, year: u16, day: u8) -> OutputFormat {
+        self.years
+            .get(&year)
+            .and_then(|y| y.days.get(&day))
+            .and_then(|d| d.output_format)
+            .unwrap_or(self.output_format)
+    }
+
+    /// The session cookie, from `AOC_SESSION` or else the session file.
+    pub fn session_token(&self) -> Result<String, ConfigError> {
+        if let Some(Secret(token)) = &self.session {
+            return Ok(token.trim().to_string());
+        }
+        let source = self.session_file.display().to_string();
+        let token = fs::read_to_string(&self.session_file).map_err(|e| {
+            ConfigError::new(
+                &source,
+                format!("{} (set AOC_SESSION or save your session cookie here)", e),
+            )
+        })?;
+        match token.trim() {
+            "" => Err(ConfigError::new(source, "session file is empty")),
+            token => Ok(token.to_string()),
+        }
+    }
+}
+
+impl Settings {
+    /// Replaces each setting that `other` has.
+    fn merge(&mut self, other: Settings) {
+        self.year = other.year.or(self.year);
+        self.day_dir = other.day_dir.or(self.day_dir.take());
+        self.input_path = other.input_path.or(self.input_path.take());
+        self.answers_path = other.answers_path.or(self.answers_path.take());
+        self.template_dir = other.template_dir.or(self.template_dir.take());
+        self.session_file = other.session_file.or(self.session_file.take());
+        self.cache_dir = other.cache_dir.or(self.cache_dir.take());
+        self.plugin_dir = other.plugin_dir.or(self.plugin_dir.take());
+        self.output_format = other.output_format.or(self.output_format);
+        self.base_url = other.base_url.or(self.base_url.take());
+    }
+}
+
+/// Substitutes the year and day into a path pattern.
+fn fill(pattern: &str, year: u16, day: u8) -> String {
+    pattern
+        .replace("{year}", &year.to_string())
+        .replace("{day:02}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+}
+
+fn year_settings(
+    source: &str,
+    year: &str,
+    raw: RawYearSettings,
+) -> Result<YearSettings, ConfigError> {
+    let key = |name: &str| format!("years.{}.{}", year, name);
+    if let Some(day_dir) = &raw.day_dir {
+        check_path(source, &key("day_dir"), day_dir, &["{day}|{day:02}"])?;
+    }
+    if let Some(input_path) = &raw.input_path {
+        check_path(source, &key("input_path"), input_path, &["{day}|{day:02}"])?;
+    }
+    if let Some(answers_path) = &raw.answers_path {
+        check_path(source, &key("answers_path"), answers_path, &[])?;
+    }
+
+    let mut days = BTreeMap::new();
+    for (day_key, settings) in raw.day {
+        let day = day_key
+            .parse::<u8>()
+            .ok()
+            .filter(|day| (1..=25).contains(day))
+            .ok_or_else(|| {
+                ConfigError::new(
+                    source,
+                    format!("[years.{}.day.{}]: days run from 1 to 25", year, day_key),
+                )
+            })?;
+        if let Some(input_path) = &settings.input_path {
+            check_path(
+                source,
+                &key(&format!("day.{}.input_path", day_key)),
+                input_path,
+                &[],
+            )?;
+        }
+        days.insert(day, settings);
+    }
+
+    Ok(YearSettings {
+        day_dir: raw.day_dir,
+        input_path: raw.input_path,
+        answers_path: raw.answers_path,
+        days,
+    })
+}
+
+/// Settings from `AOC_*` environment variables.
+fn env_settings(env: impl Fn(&str) -> Option<String>) -> Result<Settings, ConfigError> {
+    let settings = Settings {
+        year: env("AOC_YEAR")
+            .map(|year| {
+                year.parse()
+                    .map_err(|_| ConfigError::new("AOC_YEAR", format!("{:?} is not a year", year)))
+            })
+            .transpose()?,
+        day_dir: env("AOC_DAY_DIR"),
+        input_path: env("AOC_INPUT_PATH"),
+        answers_path: env("AOC_ANSWERS_PATH"),
+        template_dir: env("AOC_TEMPLATE_DIR"),
+        session_file: env("AOC_SESSION_FILE"),
+        cache_dir: env("AOC_CACHE_DIR"),
+        plugin_dir: env("AOC_PLUGIN_DIR"),
+        output_format: env("AOC_OUTPUT_FORMAT")
+            .map(|format| {
+                format
+                    .parse()
+                    .map_err(|e| ConfigError::new("AOC_OUTPUT_FORMAT", e))
+            })
+            .transpose()?,
+        base_url: env("AOC_BASE_URL"),
+    };
+    check_settings("environment", &settings)?;
+    Ok(settings)
+}
+
+fn check_settings(source: &str, settings: &Settings) -> Result<(), ConfigError> {
+    if let Some(year) = settings.year {
+        if year < 2015 {
+            return Err(ConfigError::new(
+                source,
+                format!("year: Advent of Code started in 2015, not {}", year),
+            ));
+        }
+    }
+    // Workspace-wide patterns have to keep every year and day apart
+    if let Some(day_dir) = &settings.day_dir {
+        check_path(source, "day_dir", day_dir, &["{year}", "{day}|{day:02}"])?;
+    }
+    if let Some(input_path) = &settings.input_path {
+        check_path(
+            source,
+            "input_path",
+            input_path,
+            &["{year}", "{day}|{day:02}"],
+        )?;
+    }
+    if let Some(answers_path) = &settings.answers_path {
+        check_path(source, "answers_path", answers_path, &["{year}"])?;
+    }
+    if let Some(base_url) = &settings.base_url {
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(ConfigError::new(
+                source,
+                format!("base_url: {:?} is not an http(s) URL", base_url),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks `path` is not empty and has each of the `placeholders`, where
+/// alternatives are separated by `|`.
+fn check_path(
+    source: &str,
+    key: &str,
+    path: &str,
+    placeholders: &[&str],
+) -> Result<(), ConfigError> {
+    if path.is_empty() {
+        return Err(ConfigError::new(source, format!("{}: path is empty", key)));
+    }
+    for placeholder in placeholders {
+        if !placeholder.split('|').any(|p| path.contains(p)) {
+            return Err(ConfigError::new(
+                source,
+                format!(
+                    "{}: {:?} must contain {} to tell them apart",
+                    key,
+                    path,
+                    placeholder.replace('|', " or ")
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A directory of the test's own, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> TempDir {
+            static COUNT: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "aoc-config-{}-{}",
+                std::process::id(),
+                COUNT.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Loads `toml` as the config file in `dir`, with only the variables in `env` set.
+    fn load_in(
+        dir: &Path,
+        toml: &str,
+        env: &[(&str, &str)],
+        overrides: &Settings,
+    ) -> Result<Config, ConfigError> {
+        let path = dir.join(CONFIG_FILE);
+        fs::write(&path, toml).unwrap();
+        let env = |var: &str| {
+            env.iter()
+                .find(|(k, _)| *k == var)
+                .map(|(_, v)| v.to_string())
+        };
+        Config::load_from(Some(&path), dir, env, overrides)
+    }
+
+    fn load(toml: &str, env: &[(&str, &str)], overrides: &Settings) -> Result<Config, ConfigError> {
+        load_in(&TempDir::new().0, toml, env, overrides)
+    }
+
+    #[test]
+    fn layers() {
+        let toml = "year = 2021\noutput_format = \"json\"\ncache_dir = \"cache\"\n\n[years.2023.day.7]\ninput_path = \"y2023/day07/big.txt\"\noutput_format = \"text\"\n";
+        let overrides = Settings {
+            cache_dir: Some(String::from("cli-cache")),
+            ..Default::default()
+        };
+        let config = load(
+            toml,
+            &[("AOC_YEAR", "2023"), ("AOC_CACHE_DIR", "env-cache")],
+            &overrides,
+        )
+        .unwrap();
+
+        assert_eq!(config.year, 2023);
+        assert_eq!(config.cache_dir, config.root.join("cli-cache"));
+        assert_eq!(config.plugin_dir, config.root.join("plugins"));
+        assert_eq!(config.output_format(2023, 1), OutputFormat::Json);
+        assert_eq!(config.output_format(2023, 7), OutputFormat::Text);
+        assert_eq!(config.output_format(2022, 7), OutputFormat::Json);
+        assert_eq!(
+            config.input_path(2023, 4),
+            config.root.join("y2023/day04/input.txt")
+        );
+        assert_eq!(
+            config.input_path(2023, 7),
+            config.root.join("y2023/day07/big.txt")
+        );
+    }
+
+    #[test]
+    fn year_layouts() {
+        let toml = "[years.2022]\nday_dir = \"aoc{day}\"\ninput_path = \"aoc{day}/input.txt\"\n";
+        let config = load(toml, &[], &Settings::default()).unwrap();
+
+        assert_eq!(config.day_dir(2022, 5), config.root.join("aoc5"));
+        assert_eq!(
+            config.input_path(2022, 5),
+            config.root.join("aoc5/input.txt")
+        );
+        assert_eq!(config.day_dir(2023, 5), config.root.join("y2023/day05"));
+        assert_eq!(config.day_crate(2022, 5).unwrap(), "aoc5");
+        assert_eq!(config.day_crate(2023, 5).unwrap(), "y2023_day05");
+        assert_eq!(
+            config.answers_path(2022),
+            config.root.join("answers/2022.toml")
+        );
+        assert_eq!(
+            config.year_cache_dir(2023),
+            config.root.join(".aoc-cache/2023")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        let invalid = [
+            ("yeer = 2022\n", "unknown field"),
+            ("year = 2012\n", "started in 2015"),
+            ("input_path = \"input.txt\"\n", "must contain {year}"),
+            ("day_dir = \"{year}\"\n", "must contain {day} or {day:02}"),
+            ("output_format = \"yaml\"\n", "unknown variant"),
+            ("[years.2022.day.26]\n", "days run from 1 to 25"),
+            ("[years.1999]\n", "years start at 2015"),
+            (
+                "[years.2022]\ninput_path = \"input.txt\"\n",
+                "must contain {day}",
+            ),
+        ];
+        for (toml, message) in invalid {
+            let e = load(toml, &[], &Settings::default()).unwrap_err();
+            assert!(e.to_string().contains(message), "{}: {}", toml, e);
+        }
+
+        let e = load("", &[("AOC_OUTPUT_FORMAT", "yaml")], &Settings::default()).unwrap_err();
+        assert_eq!(e.source, "AOC_OUTPUT_FORMAT");
+    }
+
+    #[test]
+    fn session_token() {
+        let dir = TempDir::new();
+        let toml = "session_file = \"~/session\"\n";
+        let home = dir.0.to_str().unwrap();
+        let config = load_in(&dir.0, toml, &[("HOME", home)], &Settings::default()).unwrap();
+        assert_eq!(config.session_file, dir.0.join("session"));
+        assert!(config.session_token().is_err());
+        fs::write(dir.0.join("session"), "\n").unwrap();
+        let e = config.session_token().unwrap_err();
+        assert_eq!(e.message, "session file is empty");
+        fs::write(dir.0.join("session"), "from-file\n").unwrap();
+        assert_eq!(config.session_token().unwrap(), "from-file");
+
+        let env = [("HOME", home), ("AOC_SESSION", " from-env\n")];
+        let config = load_in(&dir.0, toml, &env, &Settings::default()).unwrap();
+        assert_eq!(config.session_token().unwrap(), "from-env");
+        assert!(!format!("{:?}", config).contains("from-env"));
+    }
+}
@@ -0,0 +1,55 @@
+use std::str::FromStr;
+
+use serde::Serialize;
+
+/// Formats `aoc parse --dump` prints a parsed model in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpFormat {
+    Json,
+    Ron,
+}
+
+impl FromStr for DumpFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(DumpFormat::Json),
+            "ron" => Ok(DumpFormat::Ron),
+            _ => Err(format!("unknown dump format {:?}, expected json or ron", s)),
+        }
+    }
+}
+
+/// The model, pretty-printed in `format`.
+pub fn dump<T: Serialize>(model: &T, format: DumpFormat) -> Result<String, String> {
+    match format {
+        DumpFormat::Json => serde_json::to_string_pretty(model).map_err(|e| e.to_string()),
+        DumpFormat::Ron => ron::ser::to_string_pretty(model, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Move {
+        from: usize,
+        to: usize,
+    }
+
+    #[test]
+    fn formats() {
+        let model = (vec!['Z', 'N'], vec![Move { from: 1, to: 2 }]);
+        assert_eq!(
+            dump(&model, DumpFormat::Json).unwrap(),
+            "[\n  [\n    \"Z\",\n    \"N\"\n  ],\n  [\n    {\n      \"from\": 1,\n      \"to\": 2\n    }\n  ]\n]"
+        );
+        let ron = dump(&model, DumpFormat::Ron).unwrap();
+        assert!(ron.contains("'Z'") && ron.contains("from: 1"), "{}", ron);
+        assert_eq!("ron".parse(), Ok(DumpFormat::Ron));
+        assert!("yaml".parse::<DumpFormat>().is_err());
+    }
+}
@@ -0,0 +1,60 @@
+/// The 64-bit FNV-1a hash. Unlike `DefaultHasher` its output is specified,
+/// so hashes stored on disk, like the cache's keys, stay valid across builds
+/// and Rust versions. `const`, so a solver's version is hashed as it builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fnv64(u64);
+
+impl Fnv64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub const fn new() -> Fnv64 {
+        Fnv64(Fnv64::OFFSET_BASIS)
+    }
+
+    pub const fn write(mut self, bytes: &[u8]) -> Fnv64 {
+        let mut i = 0;
+        while i < bytes.len() {
+            self.0 = (self.0 ^ bytes[i] as u64).wrapping_mul(Fnv64::PRIME);
+            i += 1;
+        }
+        self
+    }
+
+    pub const fn write_u64(self, n: u64) -> Fnv64 {
+        self.write(&n.to_le_bytes())
+    }
+
+    /// Writes `bytes` after their length, so consecutive writes can't run
+    /// into each other.
+    pub const fn write_sized(self, bytes: &[u8]) -> Fnv64 {
+        self.write_u64(bytes.len() as u64).write(bytes)
+    }
+
+    pub const fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for Fnv64 {
+    fn default() -> Self {
+        Fnv64::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_hashes() {
+        // From the FNV reference test vectors
+        assert_eq!(Fnv64::new().finish(), 0xcbf29ce484222325);
+        assert_eq!(Fnv64::new().write(b"a").finish(), 0xaf63dc4c8601ec8c);
+        assert_eq!(Fnv64::new().write(b"foobar").finish(), 0x85944171f73967e8);
+        assert_ne!(
+            Fnv64::new().write_sized(b"ab").write_sized(b"c").finish(),
+            Fnv64::new().write_sized(b"a").write_sized(b"bc").finish()
+        );
+    }
+}
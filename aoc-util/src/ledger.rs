@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Answer, ConfigError};
+
+/// A year's known answers, stored as TOML with a `[day.N]` table of
+/// `part1`/`part2` per day.
+#[derive(Debug, Clone)]
+pub struct Ledger {
+    path: PathBuf,
+    days: BTreeMap<u8, [Option<Answer>; 2]>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LedgerFile {
+    #[serde(default)]
+    day: BTreeMap<String, DayAnswers>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+impl Ledger {
+    /// Reads the ledger at `path`, which is empty if the file does not exist yet.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Ledger, ConfigError> {
+        let path = path.as_ref();
+        let source = path.display().to_string();
+        let file = match fs::read_to_string(path) {
+            Ok(text) => toml::from_str::<LedgerFile>(&text)
+                .map_err(|e| ConfigError::new(&source, e.to_string().trim_end()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => LedgerFile::default(),
+            Err(e) => return Err(ConfigError::new(source, e.to_string())),
+        };
+
+        let mut days = BTreeMap::new();
+        for (key, answers) in file.day {
+            let day = key
+                .parse::<u8>()
+                .ok()
+                .filter(|day| (1..=25).contains(day))
+                .ok_or_else(|| {
+                    ConfigError::new(&source, format!("[day.{}]: days run from 1 to 25", key))
+                })?;
+            let parse = |part: Option<String>, name: &str| {
+                part.map(|stored| stored.parse::<Answer>())
+                    .transpose()
+                    .map_err(|e| ConfigError::new(&source, format!("day.{}.{}: {}", key, name, e)))
+            };
+            days.insert(
+                day,
+                [
+                    parse(answers.part1, "part1")?,
+                    parse(answers.part2, "part2")?,
+                ],
+            );
+        }
+
+        Ok(Ledger {
+            path: path.to_path_buf(),
+            days,
+        })
+    }
+
+    /// The known answer to `part` (1 or 2) of the day.
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.days.get(&day)?[usize::from(part) - 1].as_ref()
+    }
+
+    /// Records the answer to `part` (1 or 2) of the day.
+    pub fn set(&mut self, day: u8, part: u8, answer: Answer) {
+        self.days.entry(day).or_default()[usize::from(part) - 1] = Some(answer);
+    }
+
+    /// Writes the ledger back to the file it was loaded from.
+    pub fn save(&self) -> io::Result<()> {
+        let file = LedgerFile {
+            day: self
+                .days
+                .iter()
+                .map(|(day, [part1, part2])| {
+                    let answers = DayAnswers {
+                        part1: part1.as_ref().map(Answer::to_string),
+                        part2: part2.as_ref().map(Answer::to_string),
+                    };
+                    (day.to_string(), answers)
+                })
+                .collect(),
+        };
+        let text =
+            toml::to_string(&file).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("aoc-ledger-{}", std::process::id()));
+        let path = dir.join("2022.toml");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut ledger = Ledger::load(&path).unwrap();
+        assert_eq!(ledger.get(5, 1), None);
+        ledger.set(5, 1, Answer::from("CMZ"));
+        ledger.set(
+            10,
+            2,
+            Answer::Grid(vec![String::from("#.#"), String::from(".#.")]),
+        );
+        ledger.set(4, 2, Answer::from(4u32));
+        ledger.save().unwrap();
+
+        let ledger = Ledger::load(&path).unwrap();
+        assert_eq!(ledger.get(5, 1), Some(&Answer::from("CMZ")));
+        assert_eq!(ledger.get(5, 2), None);
+        assert_eq!(ledger.get(4, 2), Some(&Answer::from(4u64)));
+        assert_eq!(
+            ledger.get(10, 2).map(|a| a.to_string()),
+            Some(String::from("#.#\n.#."))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        let dir = std::env::temp_dir().join(format!("aoc-ledger-invalid-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2022.toml");
+
+        fs::write(&path, "[day.26]\npart1 = \"1\"\n").unwrap();
+        assert!(Ledger::load(&path)
+            .unwrap_err()
+            .message
+            .contains("days run from 1 to 25"));
+        fs::write(&path, "[day.1]\npart3 = \"1\"\n").unwrap();
+        assert!(Ledger::load(&path)
+            .unwrap_err()
+            .message
+            .contains("unknown field"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,59 @@
+use std::any::Any;
 use std::fs::File;
 use std::io::{self, BufRead};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
+
+mod alloc;
+mod answer;
+mod cache;
+mod config;
+mod dump;
+mod hash;
+mod ledger;
+mod normalize;
+mod part;
+mod plugin;
+mod snapshot;
+mod span;
+
+pub use alloc::{AllocStats, CountingAlloc};
+pub use answer::Answer;
+pub use cache::{input_hash, AnswerCache, CacheKey};
+pub use config::{
+    Config, ConfigError, DaySettings, OutputFormat, Settings, YearSettings, CONFIG_FILE,
+};
+pub use dump::{dump, DumpFormat};
+pub use hash::Fnv64;
+pub use ledger::Ledger;
+pub use normalize::Normalize;
+pub use part::{model_of, Dumper, Model, Parser, Part, Solver};
+pub use plugin::{
+    export as plugin_export, PluginEntry, PluginInfo, PluginParse, PluginSolve, PLUGIN_ABI_VERSION,
+    PLUGIN_ENTRY,
+};
+pub use snapshot::{assert_snapshot_in, UPDATE_SNAPSHOTS};
+pub use span::{span, Span, SpanRecord, Spans};
+
+/// Hash of this crate's sources, the locked dependencies and the compiler,
+/// which a solver's version includes as its build depends on them too.
+pub const BUILD_VERSION: u64 = include!(concat!(env!("OUT_DIR"), "/build_version.rs"));
+
+/// Time allowed for parsing plus either part of a day on its real input, in a
+/// release build, unless its crate sets a `TIME_BUDGET` of its own.
+pub const DEFAULT_TIME_BUDGET: Duration = Duration::from_secs(1);
+
+/// The message a caught panic was raised with.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => String::from(*message),
+            Err(_) => String::from("unknown panic"),
+        },
+    }
+}
 
 pub fn input_file_path() -> PathBuf {
     input_file_path_with_name("input.txt")
@@ -18,3 +70,55 @@ where
     let file = File::open(filename)?;
     Ok(io::BufReader::new(file).lines())
 }
+
+/// Reads the whole input file, applying `normalize`, and splits it into lines.
+pub fn read_normalized_lines<P>(
+    filename: P,
+    normalize: Normalize,
+) -> io::Result<impl Iterator<Item = String>>
+where
+    P: AsRef<Path> + std::fmt::Debug,
+{
+    let input = std::fs::read_to_string(filename)?;
+    Ok(normalize
+        .apply(&input)
+        .lines()
+        .map(String::from)
+        .collect::<Vec<_>>()
+        .into_iter())
+}
+
+/// Error returned when a day's puzzle input does not match the expected format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-indexed input line the error was found on, if known.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(message: S) -> Self {
+        ParseError {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at_line<S: Into<String>>(line: usize, message: S) -> Self {
+        ParseError {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,89 @@
+/// Cleanup applied to raw puzzle input before a day parses it.
+///
+/// Each day declares the normalizations that are safe for its input format,
+/// usually one of the presets below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Normalize {
+    /// Drop a leading UTF-8 byte order mark.
+    pub strip_bom: bool,
+    /// Turn CRLF line endings into LF.
+    pub unix_line_endings: bool,
+    /// End the input with exactly one newline, dropping trailing blank lines.
+    pub single_trailing_newline: bool,
+    /// Strip trailing whitespace from every line.
+    pub trim_line_ends: bool,
+}
+
+impl Normalize {
+    /// Leaves the input untouched.
+    pub const NONE: Normalize = Normalize {
+        strip_bom: false,
+        unix_line_endings: false,
+        single_trailing_newline: false,
+        trim_line_ends: false,
+    };
+
+    /// Safe for any line-based format.
+    pub const STANDARD: Normalize = Normalize {
+        strip_bom: true,
+        unix_line_endings: true,
+        single_trailing_newline: true,
+        trim_line_ends: false,
+    };
+
+    /// For formats where trailing whitespace is never significant.
+    pub const TRIMMED: Normalize = Normalize {
+        trim_line_ends: true,
+        ..Normalize::STANDARD
+    };
+
+    pub fn apply(&self, input: &str) -> String {
+        let input = match self.strip_bom {
+            true => input.strip_prefix('\u{feff}').unwrap_or(input),
+            false => input,
+        };
+        let mut output = match self.unix_line_endings {
+            true => input.replace("\r\n", "\n"),
+            false => String::from(input),
+        };
+        if self.trim_line_ends {
+            output = output
+                .split('\n')
+                .map(str::trim_end)
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+        if self.single_trailing_newline {
+            output.truncate(output.trim_end_matches(['\r', '\n']).len());
+            if !output.is_empty() {
+                output.push('\n');
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard() {
+        let input = "\u{feff}30373\r\n25512  \r\n\r\n\r\n";
+        assert_eq!(Normalize::STANDARD.apply(input), "30373\n25512  \n");
+        assert_eq!(Normalize::STANDARD.apply("30373"), "30373\n");
+        assert_eq!(Normalize::STANDARD.apply("\n\n"), "");
+    }
+
+    #[test]
+    fn trimmed() {
+        let input = "    [D]    \r\n[N] [C]    \n";
+        assert_eq!(Normalize::TRIMMED.apply(input), "    [D]\n[N] [C]\n");
+    }
+
+    #[test]
+    fn none() {
+        let input = "\u{feff}a \r\n\n";
+        assert_eq!(Normalize::NONE.apply(input), input);
+    }
+}
@@ -0,0 +1,54 @@
+use std::any::Any;
+use std::vec::IntoIter;
+
+use crate::{input_file_path, read_normalized_lines, Answer, DumpFormat, Normalize, ParseError};
+
+/// A day's parsed input, shared by both parts.
+pub type Model = Box<dyn Any>;
+
+/// Parses a day's normalized input lines into its model.
+pub type Parser = fn(IntoIter<String>) -> Result<Model, ParseError>;
+
+/// Solves one part of a day from its parsed model.
+pub type Solver = fn(&dyn Any) -> Answer;
+
+/// Prints a day's parsed model in a [`DumpFormat`], for `aoc parse --dump`.
+pub type Dumper = fn(&dyn Any, DumpFormat) -> Result<String, String>;
+
+/// One part of a day's solution, as registered with `#[aoc(day = .., part = ..)]`.
+#[derive(Clone, Copy)]
+pub struct Part {
+    pub day: u8,
+    pub part: u8,
+    /// Name of an alternative implementation, or `None` for the solution.
+    pub variant: Option<&'static str>,
+    /// Hash of the solver crate's sources when it was built, so answers
+    /// cached by an older build are not reused.
+    pub version: u64,
+    pub normalize: Normalize,
+    pub parse: Parser,
+    pub solve: Solver,
+    pub dump: Dumper,
+}
+
+impl Part {
+    /// Entry point of the part's binary: solves `input.txt` and prints the answer.
+    pub fn main(&self) {
+        let lines = read_normalized_lines(input_file_path(), self.normalize)
+            .expect("Could not parse input file lines");
+        let model = (self.parse)(lines.collect::<Vec<_>>().into_iter()).expect("Failed to parse");
+        println!("{}", (self.solve)(&*model));
+    }
+}
+
+/// The model boxed by a [`Parser`], as the type `parse` returns. Used by
+/// `#[aoc]` so solvers are checked against their day's parser.
+#[doc(hidden)]
+pub fn model_of<T: 'static>(
+    _parse: fn(IntoIter<String>) -> Result<T, ParseError>,
+    model: &dyn Any,
+) -> &T {
+    model
+        .downcast_ref()
+        .expect("model was parsed by another day")
+}
@@ -0,0 +1,165 @@
+use std::ffi::{c_char, c_void, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{Model, Part};
+
+/// Version of the plugin ABI, bumped whenever [`PluginInfo`] or the functions
+/// it points to change.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// Name of the [`PluginEntry`] every plugin exports.
+pub const PLUGIN_ENTRY: &str = "aoc_plugin";
+
+/// Describes the plugin. The description lives as long as the library is loaded.
+pub type PluginEntry = unsafe extern "C" fn() -> *const PluginInfo;
+
+/// Parses `len` bytes of raw UTF-8 input, returning the model, or null after
+/// pointing `error` at a message.
+pub type PluginParse =
+    unsafe extern "C" fn(input: *const u8, len: usize, error: *mut *mut c_char) -> *mut c_void;
+
+/// Solves a part from a model returned by [`PluginParse`], returning the answer
+/// in its stored form, or null after pointing `error` at a message.
+pub type PluginSolve =
+    unsafe extern "C" fn(model: *const c_void, error: *mut *mut c_char) -> *mut c_char;
+
+/// A day solved by a plugin. Models and strings the plugin returns are released
+/// with its `free_model` and `free_string`.
+#[repr(C)]
+pub struct PluginInfo {
+    pub abi_version: u32,
+    /// NUL-terminated name the plugin is selected by.
+    pub name: *const c_char,
+    pub year: u16,
+    pub day: u8,
+    pub parse: PluginParse,
+    pub parts: [PluginSolve; 2],
+    pub free_model: unsafe extern "C" fn(model: *mut c_void),
+    pub free_string: unsafe extern "C" fn(string: *mut c_char),
+}
+
+// The name points at a static string
+unsafe impl Sync for PluginInfo {}
+
+/// Exports the `aoc_plugin` entry point from a `cdylib` crate, solving a day
+/// with the parts registered by `#[aoc]`:
+///
+/// ```ignore
+/// util::export_plugin!("aoc7-fast", 2022, aoc7::PART1, aoc7::PART2);
+/// ```
+#[macro_export]
+macro_rules! export_plugin {
+    ($name:literal, $year:literal, $part1:expr, $part2:expr) => {
+        const _: () = {
+            use ::std::ffi::{c_char, c_void};
+            use $crate::plugin_export as export;
+
+            const PARTS: [$crate::Part; 2] = [$part1, $part2];
+            static INFO: $crate::PluginInfo = $crate::PluginInfo {
+                abi_version: $crate::PLUGIN_ABI_VERSION,
+                name: concat!($name, "\0").as_ptr().cast(),
+                year: $year,
+                day: PARTS[0].day,
+                parse,
+                parts: [part1, part2],
+                free_model: export::free_model,
+                free_string: export::free_string,
+            };
+
+            unsafe extern "C" fn parse(
+                input: *const u8,
+                len: usize,
+                error: *mut *mut c_char,
+            ) -> *mut c_void {
+                export::parse(&PARTS[0], input, len, error)
+            }
+
+            unsafe extern "C" fn part1(
+                model: *const c_void,
+                error: *mut *mut c_char,
+            ) -> *mut c_char {
+                export::solve(&PARTS[0], model, error)
+            }
+
+            unsafe extern "C" fn part2(
+                model: *const c_void,
+                error: *mut *mut c_char,
+            ) -> *mut c_char {
+                export::solve(&PARTS[1], model, error)
+            }
+
+            #[no_mangle]
+            pub extern "C" fn aoc_plugin() -> *const $crate::PluginInfo {
+                &INFO
+            }
+        };
+    };
+}
+
+/// The plugin side of the ABI, used by [`export_plugin!`].
+#[doc(hidden)]
+pub mod export {
+    use super::*;
+
+    /// # Safety
+    /// `input` must point to `len` readable bytes and `error` must be writable.
+    pub unsafe fn parse(
+        part: &Part,
+        input: *const u8,
+        len: usize,
+        error: *mut *mut c_char,
+    ) -> *mut c_void {
+        let input = std::slice::from_raw_parts(input, len);
+        let parsed = caught(|| {
+            let input = std::str::from_utf8(input).map_err(|e| e.to_string())?;
+            let lines = part.normalize.apply(input);
+            let lines = lines.lines().map(String::from).collect::<Vec<_>>();
+            (part.parse)(lines.into_iter()).map_err(|e| e.to_string())
+        });
+        match parsed {
+            Ok(model) => Box::into_raw(Box::new(model)).cast(),
+            Err(message) => {
+                *error = c_string(message);
+                ptr::null_mut()
+            }
+        }
+    }
+
+    /// # Safety
+    /// `model` must come from [`parse`] of the same plugin and `error` must be writable.
+    pub unsafe fn solve(part: &Part, model: *const c_void, error: *mut *mut c_char) -> *mut c_char {
+        let model = &*model.cast::<Model>();
+        match caught(|| Ok((part.solve)(&**model).to_string())) {
+            Ok(answer) => c_string(answer),
+            Err(message) => {
+                *error = c_string(message);
+                ptr::null_mut()
+            }
+        }
+    }
+
+    /// # Safety
+    /// `model` must come from [`parse`] and not have been freed.
+    pub unsafe extern "C" fn free_model(model: *mut c_void) {
+        drop(Box::from_raw(model.cast::<Model>()));
+    }
+
+    /// # Safety
+    /// `string` must have been returned by this plugin and not have been freed.
+    pub unsafe extern "C" fn free_string(string: *mut c_char) {
+        drop(CString::from_raw(string));
+    }
+
+    /// Runs `f`, turning a panic into an error so it does not unwind into the runner.
+    fn caught<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+        panic::catch_unwind(AssertUnwindSafe(f))
+            .unwrap_or_else(|payload| Err(format!("panicked: {}", crate::panic_message(payload))))
+    }
+
+    fn c_string(text: String) -> *mut c_char {
+        CString::new(text.replace('\0', "\\0"))
+            .expect("NUL bytes were escaped")
+            .into_raw()
+    }
+}
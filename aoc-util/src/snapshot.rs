@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::Path;
+
+/// Set to anything but `0` to write the actual values of snapshot assertions
+/// instead of comparing against the stored ones.
+pub const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// Compares `text` with the snapshot `name` stored in the calling crate's
+/// `snapshots/` directory, panicking with a diff when they differ.
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $text:expr $(,)?) => {
+        $crate::assert_snapshot_in(
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")),
+            $name,
+            &$text,
+        )
+    };
+}
+
+/// Like [`assert_snapshot!`], storing the pretty `Debug` form of `value`.
+#[macro_export]
+macro_rules! assert_debug_snapshot {
+    ($name:expr, $value:expr $(,)?) => {
+        $crate::assert_snapshot!($name, format!("{:#?}", $value))
+    };
+}
+
+/// Compares `actual` with `<crate_dir>/snapshots/<name>.snap`, or writes it there
+/// when [`UPDATE_SNAPSHOTS`] is set.
+pub fn assert_snapshot_in(crate_dir: &Path, name: &str, actual: &str) {
+    let update = std::env::var(UPDATE_SNAPSHOTS).is_ok_and(|v| v != "0");
+    if let Err(message) = check(&crate_dir.join("snapshots"), name, actual, update) {
+        panic!("{}", message);
+    }
+}
+
+fn check(dir: &Path, name: &str, actual: &str, update: bool) -> Result<(), String> {
+    let path = dir.join(format!("{}.snap", name));
+    let mut actual = actual.to_string();
+    if !actual.ends_with('\n') {
+        actual.push('\n');
+    }
+
+    let stored = fs::read_to_string(&path).ok();
+    if stored.as_deref() == Some(actual.as_str()) {
+        return Ok(());
+    }
+    if update {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        return fs::write(&path, actual).map_err(|e| format!("{}: {}", path.display(), e));
+    }
+    match stored {
+        None => Err(format!(
+            "snapshot {} does not exist, run with {}=1 to create it:\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS,
+            actual
+        )),
+        Some(stored) => Err(format!(
+            "snapshot {} does not match (-stored +actual), run with {}=1 to update it:\n{}",
+            path.display(),
+            UPDATE_SNAPSHOTS,
+            diff(&stored, &actual)
+        )),
+    }
+}
+
+/// Line diff of `old` and `new`, with `-` and `+` marking removed and added lines.
+fn diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut out = String::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            out += &format!(" {}\n", old[i]);
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            out += &format!("-{}\n", old[i]);
+            i += 1;
+        } else {
+            out += &format!("+{}\n", new[j]);
+            j += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diffs_lines() {
+        assert_eq!(diff("a\nb\nc\n", "a\nx\nc\nd\n"), " a\n-b\n+x\n c\n+d\n");
+        assert_eq!(diff("", "a\n"), "+a\n");
+    }
+
+    #[test]
+    fn creates_compares_and_updates() {
+        let dir = std::env::temp_dir().join(format!("aoc-snapshots-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let missing = check(&dir, "value", "one\ntwo", false).unwrap_err();
+        assert!(missing.contains("does not exist"), "{}", missing);
+
+        check(&dir, "value", "one\ntwo", true).unwrap();
+        check(&dir, "value", "one\ntwo", false).unwrap();
+
+        let mismatch = check(&dir, "value", "one\nthree", false).unwrap_err();
+        assert!(mismatch.ends_with(" one\n-two\n+three\n"), "{}", mismatch);
+
+        check(&dir, "value", "one\nthree", true).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("value.snap")).unwrap(),
+            "one\nthree\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
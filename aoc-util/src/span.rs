@@ -0,0 +1,148 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RECORDINGS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Spans recorded by the innermost [`Spans::record`] running on this thread.
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+struct Recording {
+    /// Tells recordings apart, so a span outliving its own doesn't end up in another.
+    id: usize,
+    spans: Vec<SpanRecord>,
+    depth: usize,
+}
+
+/// Records the phases solvers mark with [`span`], for the runner to break
+/// their time down by.
+///
+/// Recording is off until enabled, which leaves a span costing one atomic load:
+/// ```ignore
+/// util::Spans::enable();
+/// let (answer, spans) = util::Spans::record(|| solve(&model));
+/// ```
+pub struct Spans;
+
+/// A phase that ran while recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanRecord {
+    pub name: &'static str,
+    /// Number of spans the phase ran inside of, 0 for the outermost.
+    pub depth: usize,
+    pub start: Instant,
+    pub elapsed: Duration,
+}
+
+impl Spans {
+    pub fn enable() {
+        ENABLED.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Runs `f`, returning its result along with the spans it ran, in the
+    /// order they started. There are none unless recording is enabled.
+    pub fn record<T>(f: impl FnOnce() -> T) -> (T, Vec<SpanRecord>) {
+        if !Self::is_enabled() {
+            return (f(), vec![]);
+        }
+        let recording = Recording {
+            id: RECORDINGS.fetch_add(1, Ordering::Relaxed),
+            spans: vec![],
+            depth: 0,
+        };
+        let _outer = Restore(RECORDING.with(|r| r.replace(Some(recording))));
+        let result = f();
+        let recording = RECORDING.with(|r| r.take());
+        (result, recording.map(|r| r.spans).unwrap_or_default())
+    }
+}
+
+/// Puts back the recording a [`Spans::record`] nested in, even if it unwinds.
+struct Restore(Option<Recording>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        RECORDING.with(|r| *r.borrow_mut() = outer);
+    }
+}
+
+/// Marks a phase of a solver, which lasts until the returned guard is dropped:
+/// ```ignore
+/// let _span = util::span("tree build");
+/// ```
+#[must_use = "the span ends when the guard is dropped"]
+pub fn span(name: &'static str) -> Span {
+    if !Spans::is_enabled() {
+        return Span { index: None };
+    }
+    let index = RECORDING.with(|r| {
+        let mut recording = r.borrow_mut();
+        let recording = recording.as_mut()?;
+        recording.spans.push(SpanRecord {
+            name,
+            depth: recording.depth,
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+        });
+        recording.depth += 1;
+        Some((recording.id, recording.spans.len() - 1))
+    });
+    Span { index }
+}
+
+/// Guard returned by [`span`], ending the phase when dropped.
+pub struct Span {
+    /// Recording the phase is in and its index there, if one was running.
+    index: Option<(usize, usize)>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        let Some((id, index)) = self.index else {
+            return;
+        };
+        RECORDING.with(|r| {
+            if let Some(recording) = r.borrow_mut().as_mut().filter(|r| r.id == id) {
+                let span = &mut recording.spans[index];
+                span.elapsed = span.start.elapsed();
+                recording.depth -= 1;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record() {
+        // Spans outside a recording are ignored
+        drop(span("ignored"));
+        Spans::enable();
+        let (answer, spans) = Spans::record(|| {
+            let _outer = span("outer");
+            {
+                let _first = span("first");
+            }
+            let _second = span("second");
+            let (_, nested) = Spans::record(|| drop(span("nested")));
+            assert_eq!(nested.len(), 1);
+            // Outlives its recording, so isn't recorded in this one
+            drop(Spans::record(|| span("escaped")));
+            42
+        });
+        assert_eq!(answer, 42);
+        let names = spans.iter().map(|s| (s.name, s.depth)).collect::<Vec<_>>();
+        assert_eq!(names, [("outer", 0), ("first", 1), ("second", 1)]);
+        assert!(spans[0].elapsed >= spans[1].elapsed + spans[2].elapsed);
+    }
+}
@@ -0,0 +1,195 @@
+//! Generates the registry of the days built in, from the solvers each day's
+//! crate marks with `#[aoc]`. A day is built in with its `y{year}-day{day}`
+//! feature, and its crate is found where `aoc.toml` lays out the year's days.
+//! With the `embed-input` feature, also embeds their inputs, as `aoc.toml`
+//! locates them, so the runner needs no input files.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use syn::{File, Item, LitInt, LitStr};
+use util::{Config, Settings, CONFIG_FILE};
+
+/// A solver marked with `#[aoc(day = .., part = .., variant = ..)]`.
+struct Solver {
+    day: u8,
+    part: u8,
+    variant: Option<String>,
+}
+
+fn main() {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    let config = load_config();
+    let days = enabled_days();
+    write(&out_dir.join("registry.rs"), &registry(&config, &days));
+    write(&out_dir.join("inputs.rs"), &inputs(&config, &days));
+}
+
+fn load_config() -> Config {
+    println!("cargo:rerun-if-env-changed=AOC_DAY_DIR");
+    println!("cargo:rerun-if-env-changed=AOC_INPUT_PATH");
+    let manifest_dir = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+    let path = manifest_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE))
+        .find(|path| path.is_file());
+    if let Some(path) = &path {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    Config::load_from(
+        path.as_deref(),
+        manifest_dir.parent().unwrap_or(Path::new(".")),
+        |var| env::var(var).ok(),
+        &Settings::default(),
+    )
+    .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Year and day of each `y{year}-day{day}` feature enabled, in order.
+fn enabled_days() -> Vec<(u16, u8)> {
+    let mut days = env::vars_os()
+        .filter_map(|(name, _)| {
+            let feature = name.to_str()?.strip_prefix("CARGO_FEATURE_Y")?;
+            let (year, day) = feature.split_once("_DAY")?;
+            Some((year.parse().ok()?, day.parse().ok()?))
+        })
+        .collect::<Vec<_>>();
+    days.sort_unstable();
+    days
+}
+
+fn write(path: &Path, code: &str) {
+    fs::write(path, code).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+}
+
+/// `DAYS`, with an entry for each day from the solvers at the root of its crate.
+fn registry(config: &Config, days: &[(u16, u8)]) -> String {
+    let mut entries = String::new();
+    for &(year, day) in days {
+        // Paths name the crate with the `_` Cargo puts for `-` in its name
+        let krate = config
+            .day_crate(year, day)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .replace('-', "_");
+        let lib = config.day_dir(year, day).join("src").join("lib.rs");
+        println!("cargo:rerun-if-changed={}", lib.display());
+        let file = parse_file(&lib);
+        let solvers = solvers(&file);
+        for part in [1, 2] {
+            if !solvers
+                .iter()
+                .any(|s| s.part == part && s.variant.is_none())
+            {
+                panic!(
+                    "{}: no `#[aoc(day = {}, part = {})]` solver",
+                    lib.display(),
+                    day,
+                    part
+                );
+            }
+        }
+        if let Some(other) = solvers.iter().find(|s| s.day != day) {
+            panic!(
+                "{}: solver of day {} in the crate of day {}",
+                lib.display(),
+                other.day,
+                day
+            );
+        }
+
+        // Named as `#[aoc]` names the constants
+        let variants = solvers
+            .iter()
+            .filter_map(|s| {
+                let variant = s.variant.as_ref()?;
+                let name = variant.to_uppercase().replace('-', "_");
+                Some(format!("{}::PART{}_{}", krate, s.part, name))
+            })
+            .collect::<Vec<_>>();
+        let budget = match has_budget(&file) {
+            true => format!("{}::TIME_BUDGET", krate),
+            false => String::from("util::DEFAULT_TIME_BUDGET"),
+        };
+        write!(
+            entries,
+            "    Day::new({}, {}, [{krate}::PART1, {krate}::PART2])",
+            year,
+            budget,
+            krate = krate
+        )
+        .unwrap();
+        if !variants.is_empty() {
+            write!(entries, ".with_variants(&[{}])", variants.join(", ")).unwrap();
+        }
+        entries.push_str(",\n");
+    }
+    format!("pub const DAYS: &[Day] = &[\n{}];\n", entries)
+}
+
+fn parse_file(path: &Path) -> File {
+    let source = fs::read_to_string(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+    syn::parse_file(&source).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+/// Whether the crate sets its own `TIME_BUDGET` rather than taking the default.
+fn has_budget(file: &File) -> bool {
+    file.items
+        .iter()
+        .any(|item| matches!(item, Item::Const(c) if c.ident == "TIME_BUDGET"))
+}
+
+/// Every `#[aoc]` solver among the items of `file`.
+fn solvers(file: &File) -> Vec<Solver> {
+    let attrs = file.items.iter().flat_map(|item| match item {
+        Item::Fn(solver) => solver.attrs.as_slice(),
+        _ => &[],
+    });
+
+    let mut solvers = vec![];
+    for attr in attrs.filter(|attr| attr.path().is_ident("aoc")) {
+        let mut solver = Solver {
+            day: 0,
+            part: 0,
+            variant: None,
+        };
+        // Checked by `#[aoc]` itself, so other arguments are left to it
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("day") {
+                solver.day = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+            } else if meta.path.is_ident("part") {
+                solver.part = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+            } else if meta.path.is_ident("variant") {
+                solver.variant = Some(meta.value()?.parse::<LitStr>()?.value());
+            }
+            Ok(())
+        });
+        solvers.push(solver);
+    }
+    solvers
+}
+
+/// `EMBEDDED_INPUTS`, holding the input of each day when embedding them.
+fn inputs(config: &Config, days: &[(u16, u8)]) -> String {
+    let mut inputs = String::new();
+    if env::var_os("CARGO_FEATURE_EMBED_INPUT").is_some() {
+        for &(year, day) in days {
+            let path = config.input_path(year, day);
+            // Days missing an input load it at runtime, and are embedded
+            // once the input is added to their directory
+            if let Some(dir) = path.parent() {
+                println!("cargo:rerun-if-changed={}", dir.display());
+            }
+            if path.is_file() {
+                println!("cargo:rerun-if-changed={}", path.display());
+                let path = path.to_str().expect("input paths are UTF-8");
+                writeln!(inputs, "    ({}, {}, include_str!({:?})),", year, day, path).unwrap();
+            }
+        }
+    }
+    format!(
+        "const EMBEDDED_INPUTS: &[(u16, u8, &str)] = &[\n{}];\n",
+        inputs
+    )
+}
@@ -0,0 +1,32 @@
+use clap::{Args, Subcommand};
+use util::{AnswerCache, Config};
+
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Remove the year's cached answers
+    Clear {
+        /// Only remove the answers of this day
+        #[arg(long)]
+        day: Option<u8>,
+    },
+}
+
+/// Manages the answers `aoc run` caches.
+pub fn cache(args: CacheArgs, config: &Config) -> Result<bool, String> {
+    match args.command {
+        CacheCommand::Clear { day } => {
+            let dir = config.answer_cache_dir(config.year);
+            let removed = AnswerCache::new(&dir)
+                .clear(day)
+                .map_err(|e| format!("{}: {}", dir.display(), e))?;
+            println!("Removed {} cached answers from {}", removed, dir.display());
+        }
+    }
+    Ok(true)
+}
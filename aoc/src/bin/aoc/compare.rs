@@ -0,0 +1,223 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe, PanicHookInfo};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use util::{panic_message, Answer, Config, Solver};
+
+use crate::run::{input_files, parse_input, read_input};
+
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Day whose variants to compare
+    #[arg(long)]
+    day: u8,
+    /// Only compare this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Input file [default: the day's `input_path` from aoc.toml]
+    #[arg(long, conflicts_with = "inputs")]
+    input: Option<PathBuf>,
+    /// Directory of inputs to compare on
+    #[arg(long)]
+    inputs: Option<PathBuf>,
+    /// Times to run each variant, keeping the fastest
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+}
+
+/// Name the solution is listed under, next to its variants.
+const SOLUTION: &str = "solution";
+
+/// Runs the solution and every variant of each part of the day against the
+/// inputs, returning whether they all agreed.
+pub fn compare(args: CompareArgs, config: &Config) -> Result<bool, String> {
+    let year = config.year;
+    let day = aoc::day(year, args.day)
+        .ok_or_else(|| format!("day {} of {} is not solved yet", args.day, year))?;
+    let aoc::Code::Builtin {
+        parts: solutions,
+        variants,
+        ..
+    } = day.code
+    else {
+        unreachable!("the registry only holds built-in days");
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    let own_input = args.input.is_none() && args.inputs.is_none();
+    let inputs = match (&args.input, &args.inputs) {
+        (_, Some(dir)) => input_files(dir)?,
+        (Some(input), None) => vec![input.clone()],
+        (None, None) => vec![config.input_path(year, day.day)],
+    };
+
+    let mut agreed = true;
+    for path in inputs {
+        let input = read_input(day, &path, own_input)?;
+        println!("{} day {}, {}", day.year, day.day, path.display());
+        let model = parse_input(day, &input)
+            .0
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        for &part in &parts {
+            let mut solvers = vec![(SOLUTION, solutions[usize::from(part) - 1])];
+            solvers.extend(
+                variants
+                    .iter()
+                    .filter(|v| v.part == part)
+                    .map(|v| (v.variant.unwrap_or_default(), v.solve)),
+            );
+            if solvers.len() == 1 {
+                println!("  part {}: no variants", part);
+                continue;
+            }
+
+            // Report panics as disagreements rather than printing them as they happen
+            let quiet = QuietPanics::new();
+            let (lines, part_agreed) = compare_part(part, &solvers, &*model, args.runs);
+            drop(quiet);
+            for line in lines {
+                println!("{}", line);
+            }
+            agreed &= part_agreed;
+        }
+    }
+    Ok(agreed)
+}
+
+/// What `panic::take_hook` returns.
+type PanicHook = Box<dyn Fn(&PanicHookInfo) + Sync + Send>;
+
+/// Keeps panics from being printed while it lives, putting the panic hook it
+/// replaced back when dropped.
+struct QuietPanics(Option<PanicHook>);
+
+impl QuietPanics {
+    fn new() -> QuietPanics {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        QuietPanics(Some(previous))
+    }
+}
+
+impl Drop for QuietPanics {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            panic::set_hook(previous);
+        }
+    }
+}
+
+/// Runs each of a part's solvers, the solution first, returning the lines
+/// reporting their answer and timings, and whether they all agreed with it.
+fn compare_part(
+    part: u8,
+    solvers: &[(&str, Solver)],
+    model: &dyn Any,
+    runs: u32,
+) -> (Vec<String>, bool) {
+    let results = solvers
+        .iter()
+        .map(|&(_, solve)| fastest(solve, model, runs))
+        .collect::<Vec<_>>();
+    let expected = results[0].as_ref().ok().map(|(answer, _)| answer);
+    let mut lines = vec![];
+    match expected {
+        Some(Answer::Grid(rows)) => {
+            lines.push(format!("  part {}:", part));
+            lines.extend(rows.iter().map(|row| format!("    {}", row)));
+        }
+        Some(answer) => lines.push(format!("  part {}: {}", part, answer)),
+        None => lines.push(format!("  part {}:", part)),
+    }
+
+    let width = solvers
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+    let baseline = results[0].as_ref().ok().map(|(_, elapsed)| *elapsed);
+    let mut agreed = true;
+    for ((name, _), result) in solvers.iter().zip(&results) {
+        let status = match result {
+            Ok((answer, elapsed)) => {
+                let relative = match baseline {
+                    Some(baseline) => format!(
+                        "{:.2}x",
+                        elapsed.as_secs_f64() / baseline.as_secs_f64().max(f64::EPSILON)
+                    ),
+                    None => String::from("-"),
+                };
+                let mut status = format!("{:>10.2?}  {:>6}", elapsed, relative);
+                if Some(answer) != expected {
+                    agreed = false;
+                    status += &format!("  DISAGREES: {}", answer.to_string().replace('\n', " / "));
+                }
+                status
+            }
+            Err(message) => {
+                agreed = false;
+                format!("PANICKED: {}", message)
+            }
+        };
+        lines.push(format!("    {:<width$}  {}", name, status, width = width));
+    }
+    (lines, agreed)
+}
+
+/// The answer of `solve`, with the fastest of `runs` runs, or its panic message.
+fn fastest(solve: Solver, model: &dyn Any, runs: u32) -> Result<(Answer, Duration), String> {
+    let mut fastest = Duration::MAX;
+    let mut answer = None;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let solved = panic::catch_unwind(AssertUnwindSafe(|| solve(model)));
+        fastest = fastest.min(start.elapsed());
+        answer = Some(solved.map_err(panic_message)?);
+    }
+    Ok((answer.expect("runs is at least 1"), fastest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_variants() {
+        let model = ();
+        let agreeing: [(&str, Solver); 3] = [
+            (SOLUTION, |_| Answer::from(24000u32)),
+            ("scan", |_| Answer::from(24000usize)),
+            ("text", |_| Answer::from("24000")),
+        ];
+        let (lines, agreed) = compare_part(1, &agreeing, &model, 3);
+        assert!(agreed, "{:?}", lines);
+        assert_eq!(lines[0], "  part 1: 24000");
+        assert!(lines[2].starts_with("    scan      "), "{:?}", lines[2]);
+
+        let disagreeing: [(&str, Solver); 3] = [
+            (SOLUTION, |_| {
+                Answer::from(vec![String::from("#."), String::from(".#")])
+            }),
+            ("wrong", |_| {
+                Answer::from(vec![String::from("#."), String::from("##")])
+            }),
+            ("broken", |_| panic!("no elves")),
+        ];
+        let (lines, agreed) = compare_part(2, &disagreeing, &model, 1);
+        assert!(!agreed);
+        assert_eq!(lines[..3], ["  part 2:", "    #.", "    .#"]);
+        assert!(lines[4].ends_with("DISAGREES: #. / ##"), "{:?}", lines[4]);
+        assert_eq!(lines[5], "    broken    PANICKED: no elves");
+
+        // The variants can't be timed against a solution that panicked
+        let (lines, agreed) = compare_part(1, &[disagreeing[2], agreeing[1]], &model, 1);
+        assert!(!agreed);
+        assert_eq!(lines[0], "  part 1:");
+        assert!(lines[2].contains("-  DISAGREES: 24000"), "{:?}", lines[2]);
+    }
+}
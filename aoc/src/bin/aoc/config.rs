@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use util::{Config, OutputFormat, Settings};
+
+/// Flags overriding `aoc.toml` and the `AOC_*` environment variables.
+#[derive(Args)]
+pub struct ConfigArgs {
+    /// Config file [default: nearest aoc.toml in this or a parent directory]
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Puzzle year
+    #[arg(long, global = true)]
+    year: Option<u16>,
+    /// Output format
+    #[arg(long, global = true, value_parser = parse_format)]
+    format: Option<OutputFormat>,
+    /// File holding the adventofcode.com session cookie
+    #[arg(long, global = true)]
+    session_file: Option<String>,
+    /// Directory for cached inputs and answers
+    #[arg(long, global = true)]
+    cache_dir: Option<String>,
+    /// Directory of solution plugins
+    #[arg(long, global = true)]
+    plugin_dir: Option<String>,
+    /// Advent of Code server to fetch from
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+}
+
+impl ConfigArgs {
+    pub fn load(self) -> Result<Config, String> {
+        let overrides = Settings {
+            year: self.year,
+            session_file: self.session_file,
+            cache_dir: self.cache_dir,
+            plugin_dir: self.plugin_dir,
+            output_format: self.format,
+            base_url: self.base_url,
+            ..Default::default()
+        };
+        let config = match self.config {
+            Some(path) => {
+                let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+                Config::load_from(Some(&path), &cwd, |var| std::env::var(var).ok(), &overrides)
+            }
+            None => Config::load(&overrides),
+        };
+        config.map_err(|e| e.to_string())
+    }
+}
+
+fn parse_format(s: &str) -> Result<OutputFormat, String> {
+    s.parse()
+}
@@ -0,0 +1,55 @@
+use std::fs;
+
+use clap::Args;
+use util::Config;
+
+#[derive(Args)]
+pub struct FetchArgs {
+    /// Day to fetch the input of
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Overwrite an input that was already fetched
+    #[arg(long)]
+    force: bool,
+}
+
+/// Downloads the day's input to its configured input path.
+pub fn fetch(args: FetchArgs, config: &Config) -> Result<bool, String> {
+    let path = config.input_path(config.year, args.day);
+    if path.exists() && !args.force {
+        return Err(format!(
+            "{} already exists, pass --force to fetch it again",
+            path.display()
+        ));
+    }
+    let token = config.session_token().map_err(|e| e.to_string())?;
+
+    let url = format!("{}/{}/day/{}/input", config.base_url, config.year, args.day);
+    let input = get(&url, &token)?;
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    fs::write(&path, input).map_err(|e| format!("{}: {}", path.display(), e))?;
+    println!(
+        "Saved {} day {} input to {}",
+        config.year,
+        args.day,
+        path.display()
+    );
+    Ok(true)
+}
+
+/// Fetches `url` as the user the session `token` belongs to.
+pub fn get(url: &str, token: &str) -> Result<String, String> {
+    ureq::get(url)
+        .set("Cookie", &format!("session={}", token))
+        .set(
+            "User-Agent",
+            concat!("aoc-runner/", env!("CARGO_PKG_VERSION")),
+        )
+        .call()
+        .map_err(|e| format!("{}: {}", url, e))?
+        .into_string()
+        .map_err(|e| format!("{}: {}", url, e))
+}
@@ -0,0 +1,403 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use clap::Args;
+use util::{Config, Normalize};
+
+#[derive(Args)]
+pub struct InspectArgs {
+    /// Input file to inspect
+    #[arg(required_unless_present = "day")]
+    file: Option<PathBuf>,
+    /// Inspect the day's input at its `input_path` from aoc.toml instead
+    #[arg(long, conflicts_with = "file")]
+    day: Option<u8>,
+}
+
+/// Most line lengths listed one by one before only the range is shown.
+const LISTED_LENGTHS: usize = 8;
+
+/// Most sections listed one by one before only their sizes are summarized.
+const LISTED_SECTIONS: usize = 8;
+
+/// Most distinct characters listed for a grid.
+const LISTED_GRID_CHARS: usize = 16;
+
+/// Character classes, a set of which is kept as bits by their index.
+const CLASSES: [&str; 6] = ["digit", "lower", "upper", "space", "punct", "other"];
+
+/// What an input looks like, to choose the types and parsers of a new day by.
+#[derive(Debug, PartialEq)]
+struct Inspection {
+    lines: usize,
+    /// Number of lines of each length, in chars.
+    lengths: BTreeMap<usize, usize>,
+    /// First line (1-indexed) and line count of each run of non-blank lines.
+    sections: Vec<(usize, usize)>,
+    /// Classes of the chars seen in each column of each section, as [`CLASSES`] bits.
+    columns: Vec<Vec<u8>>,
+    numbers: Numbers,
+    grid: Option<Grid>,
+}
+
+/// Integers found in the input, with `-` as a sign only when not between two
+/// words, so ranges like `2-4` are two positive numbers.
+#[derive(Debug, Default, PartialEq)]
+struct Numbers {
+    count: usize,
+    /// Smallest and largest, if any fit in an `i128`.
+    range: Option<(i128, i128)>,
+    /// Whether some did not fit in an `i128`.
+    overflowed: bool,
+}
+
+/// A rectangular block of chars, one row per line.
+#[derive(Debug, PartialEq)]
+struct Grid {
+    width: usize,
+    height: usize,
+    chars: BTreeSet<char>,
+}
+
+/// Reads the input and prints what it looks like.
+pub fn inspect(args: InspectArgs, config: &Config) -> Result<bool, String> {
+    let path = match (args.file, args.day) {
+        (Some(file), _) => file,
+        (None, Some(day)) => config.input_path(config.year, day),
+        (None, None) => unreachable!("clap requires a file or day"),
+    };
+    let lines = util::read_normalized_lines(&path, Normalize::STANDARD)
+        .map_err(|e| format!("{}: {}", path.display(), e))?
+        .collect::<Vec<_>>();
+    let inspection = Inspection::of(&lines);
+
+    println!(
+        "{}: {} in {}",
+        path.display(),
+        plural(inspection.lines, "line"),
+        plural(inspection.sections.len(), "section")
+    );
+    for line in inspection.report() {
+        println!("  {}", line);
+    }
+    Ok(true)
+}
+
+impl Inspection {
+    fn of(lines: &[String]) -> Inspection {
+        let mut inspection = Inspection {
+            lines: lines.len(),
+            lengths: BTreeMap::new(),
+            sections: vec![],
+            columns: vec![],
+            numbers: Numbers::default(),
+            grid: None,
+        };
+        let mut section_start = None;
+        for (i, line) in lines.iter().enumerate() {
+            let chars = line.chars().collect::<Vec<_>>();
+            *inspection.lengths.entry(chars.len()).or_default() += 1;
+
+            match (line.trim().is_empty(), section_start) {
+                (false, None) => {
+                    section_start = Some(i);
+                    inspection.columns.push(vec![]);
+                }
+                (true, Some(start)) => {
+                    inspection.sections.push((start + 1, i - start));
+                    section_start = None;
+                }
+                _ => {}
+            }
+
+            if let (Some(columns), Some(_)) = (inspection.columns.last_mut(), section_start) {
+                if columns.len() < chars.len() {
+                    columns.resize(chars.len(), 0);
+                }
+                for (column, &c) in chars.iter().enumerate() {
+                    columns[column] |= class(c);
+                }
+            }
+
+            inspection.numbers.scan(&chars);
+        }
+        if let Some(start) = section_start {
+            inspection.sections.push((start + 1, lines.len() - start));
+        }
+
+        inspection.grid = Grid::of(lines, &inspection.sections);
+        inspection
+    }
+
+    /// The inspection as lines of text.
+    fn report(&self) -> Vec<String> {
+        let mut report = vec![];
+
+        let lengths = self
+            .lengths
+            .iter()
+            .flat_map(|(&len, &count)| vec![len; count]);
+        let lengths = lengths.collect::<Vec<_>>();
+        if let (Some(min), Some(max)) = (lengths.first(), lengths.last()) {
+            let median = lengths[lengths.len() / 2];
+            let mean = lengths.iter().sum::<usize>() as f64 / lengths.len() as f64;
+            report.push(format!(
+                "line length: {} to {}, median {}, mean {:.1}",
+                min, max, median, mean
+            ));
+            if self.lengths.len() > 1 && self.lengths.len() <= LISTED_LENGTHS {
+                for (len, count) in &self.lengths {
+                    report.push(format!("  {:>5} chars: {}", len, plural(*count, "line")));
+                }
+            }
+        }
+
+        if self.sections.len() > 1 && self.sections.len() <= LISTED_SECTIONS {
+            report.push(String::from("sections:"));
+            for (start, count) in &self.sections {
+                report.push(format!(
+                    "  lines {} to {} ({})",
+                    start,
+                    start + count - 1,
+                    plural(*count, "line")
+                ));
+            }
+        } else if self.sections.len() > LISTED_SECTIONS {
+            let sizes = self.sections.iter().map(|(_, count)| *count);
+            report.push(format!(
+                "sections: {} to {} lines each",
+                sizes.clone().min().unwrap_or_default(),
+                sizes.max().unwrap_or_default()
+            ));
+        }
+
+        // Sections are shown apart only when they look different, not when
+        // some just have more columns of the same kind
+        let widest = self.columns.iter().max_by_key(|columns| columns.len());
+        let widest = widest.cloned().unwrap_or_default();
+        let alike = self.columns.iter().all(|c| widest.starts_with(c));
+        if alike || self.sections.len() > LISTED_SECTIONS {
+            let mut merged = widest;
+            for columns in &self.columns {
+                for (column, classes) in columns.iter().enumerate() {
+                    merged[column] |= classes;
+                }
+            }
+            report.push(String::from("columns:"));
+            report.extend(column_runs(&merged));
+        } else {
+            for ((start, count), columns) in self.sections.iter().zip(&self.columns) {
+                report.push(format!(
+                    "columns of lines {} to {}:",
+                    start,
+                    start + count - 1
+                ));
+                report.extend(column_runs(columns));
+            }
+        }
+
+        report.push(match (self.numbers.count, self.numbers.range) {
+            (0, _) => String::from("numbers: none"),
+            (count, Some((min, max))) => format!(
+                "numbers: {} from {} to {}, fit in {}{}",
+                count,
+                min,
+                max,
+                smallest_type(min, max),
+                match self.numbers.overflowed {
+                    true => ", some do not fit in 128 bits",
+                    false => "",
+                }
+            ),
+            (count, None) => format!("numbers: {}, none fit in 128 bits", count),
+        });
+
+        report.push(match &self.grid {
+            Some(grid) if grid.chars.len() <= LISTED_GRID_CHARS => format!(
+                "grid: {} x {} of {:?}",
+                grid.width,
+                grid.height,
+                grid.chars.iter().collect::<String>()
+            ),
+            Some(grid) => format!(
+                "grid: {} x {} of {} distinct chars",
+                grid.width,
+                grid.height,
+                grid.chars.len()
+            ),
+            None => String::from("grid: no"),
+        });
+        report
+    }
+}
+
+impl Numbers {
+    fn scan(&mut self, chars: &[char]) {
+        let mut i = 0;
+        while i < chars.len() {
+            if !chars[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let negative = start > 0
+                && chars[start - 1] == '-'
+                && (start < 2 || !chars[start - 2].is_alphanumeric());
+            let digits = chars[start..i].iter().collect::<String>();
+
+            self.count += 1;
+            match digits.parse::<i128>() {
+                Ok(n) => {
+                    let n = if negative { -n } else { n };
+                    self.range = Some(match self.range {
+                        Some((min, max)) => (min.min(n), max.max(n)),
+                        None => (n, n),
+                    });
+                }
+                Err(_) => self.overflowed = true,
+            }
+        }
+    }
+}
+
+impl Grid {
+    /// The grid the lines make, if they are a single section of two or more
+    /// equally long lines.
+    fn of(lines: &[String], sections: &[(usize, usize)]) -> Option<Grid> {
+        let &[(start, height)] = sections else {
+            return None;
+        };
+        let rows = &lines[start - 1..start - 1 + height];
+        let width = rows[0].chars().count();
+        if height < 2 || width < 2 || rows.iter().any(|row| row.chars().count() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height,
+            chars: rows.iter().flat_map(|row| row.chars()).collect(),
+        })
+    }
+}
+
+/// Runs of columns with the same classes, as lines of text.
+fn column_runs(columns: &[u8]) -> Vec<String> {
+    let mut runs = vec![];
+    let mut start = 0;
+    for end in 1..=columns.len() {
+        if end < columns.len() && columns[end] == columns[start] {
+            continue;
+        }
+        let label = match end - start {
+            1 => format!("{}", start),
+            _ => format!("{}-{}", start, end - 1),
+        };
+        runs.push(format!("  {:>9}: {}", label, class_names(columns[start])));
+        start = end;
+    }
+    runs
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {}", noun),
+        _ => format!("{} {}s", count, noun),
+    }
+}
+
+/// The bit of the class of `c` in [`CLASSES`].
+fn class(c: char) -> u8 {
+    let index = match c {
+        '0'..='9' => 0,
+        'a'..='z' => 1,
+        'A'..='Z' => 2,
+        ' ' | '\t' => 3,
+        c if c.is_ascii_punctuation() => 4,
+        _ => 5,
+    };
+    1 << index
+}
+
+fn class_names(classes: u8) -> String {
+    let names = CLASSES
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| classes & 1 << i != 0)
+        .map(|(_, name)| *name);
+    names.collect::<Vec<_>>().join(" | ")
+}
+
+/// The narrowest integer type holding every number from `min` to `max`.
+fn smallest_type(min: i128, max: i128) -> &'static str {
+    if min >= 0 {
+        match max {
+            n if n <= u8::MAX.into() => "u8",
+            n if n <= u16::MAX.into() => "u16",
+            n if n <= u32::MAX.into() => "u32",
+            n if n <= u64::MAX.into() => "u64",
+            _ => "u128",
+        }
+    } else {
+        let fits = |lo: i128, hi: i128| lo <= min && max <= hi;
+        match () {
+            _ if fits(i8::MIN.into(), i8::MAX.into()) => "i8",
+            _ if fits(i16::MIN.into(), i16::MAX.into()) => "i16",
+            _ if fits(i32::MIN.into(), i32::MAX.into()) => "i32",
+            _ if fits(i64::MIN.into(), i64::MAX.into()) => "i64",
+            _ => "i128",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inspect(input: &str) -> Inspection {
+        Inspection::of(&input.lines().map(String::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn sections_and_numbers() {
+        let inspection = inspect("2-4,6-8\n2-3,4-5\n\n\nx=-12, y=300\n");
+        assert_eq!(inspection.lines, 5);
+        assert_eq!(inspection.sections, [(1, 2), (5, 1)]);
+        assert_eq!(
+            inspection.numbers,
+            Numbers {
+                count: 10,
+                range: Some((-12, 300)),
+                overflowed: false
+            }
+        );
+        assert_eq!(smallest_type(-12, 300), "i16");
+        assert_eq!(smallest_type(0, 300), "u16");
+        assert_eq!(inspection.grid, None);
+        assert_eq!(class_names(inspection.columns[0][1]), "punct");
+        assert_eq!(class_names(inspection.columns[1][0]), "lower");
+        assert!(inspection
+            .report()
+            .contains(&String::from("columns of lines 1 to 2:")));
+    }
+
+    #[test]
+    fn grid() {
+        let inspection = inspect("30373\n25512\n65332\n");
+        assert_eq!(
+            inspection.grid,
+            Some(Grid {
+                width: 5,
+                height: 3,
+                chars: "0123567".chars().collect()
+            })
+        );
+        assert!(inspection
+            .report()
+            .contains(&String::from("grid: 5 x 3 of \"0123567\"")));
+        assert!(inspect("30373\n2551\n").grid.is_none());
+        assert!(inspect("ab\ncd\n\nef\n").grid.is_none());
+    }
+}
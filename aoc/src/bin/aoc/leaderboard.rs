@@ -0,0 +1,324 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Deserialize;
+use util::Config;
+
+use crate::fetch;
+
+#[derive(Args)]
+pub struct LeaderboardArgs {
+    /// Private leaderboard JSON export to read
+    #[arg(required_unless_present = "id")]
+    file: Option<PathBuf>,
+    /// Fetch the export of this private leaderboard instead, using the session cookie
+    #[arg(long, conflicts_with = "file")]
+    id: Option<u64>,
+    /// Also write the standings as CSV, one row per member and day
+    #[arg(long)]
+    csv: Option<PathBuf>,
+}
+
+/// Puzzles unlock at midnight EST, which is this many seconds into the UTC day.
+const UNLOCK_OFFSET: i64 = 5 * 60 * 60;
+
+/// A private leaderboard as exported by `/<year>/leaderboard/private/view/<id>.json`.
+#[derive(Deserialize)]
+struct Export {
+    event: String,
+    members: BTreeMap<String, Member>,
+}
+
+#[derive(Deserialize)]
+struct Member {
+    id: u64,
+    /// Missing for anonymous members.
+    name: Option<String>,
+    /// Stars by day, then by part.
+    #[serde(default)]
+    completion_day_level: BTreeMap<u8, BTreeMap<u8, Star>>,
+}
+
+#[derive(Deserialize)]
+struct Star {
+    get_star_ts: i64,
+    /// Orders stars earned in the same second.
+    #[serde(default)]
+    star_index: u64,
+}
+
+/// A member's position on the leaderboard.
+#[derive(Debug, PartialEq)]
+struct Standing {
+    id: u64,
+    name: String,
+    local_score: usize,
+    /// When each part of each day was solved, as a Unix timestamp.
+    stars: BTreeMap<u8, [Option<i64>; 2]>,
+}
+
+impl Standing {
+    fn star_count(&self) -> usize {
+        self.stars.values().flatten().flatten().count()
+    }
+
+    fn last_star(&self) -> Option<i64> {
+        self.stars.values().flatten().flatten().copied().max()
+    }
+}
+
+/// Prints the standings of a private leaderboard, read from an export or fetched.
+pub fn leaderboard(args: LeaderboardArgs, config: &Config) -> Result<bool, String> {
+    let (source, json) = match (&args.file, args.id) {
+        (Some(path), _) => (
+            path.display().to_string(),
+            fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?,
+        ),
+        (None, Some(id)) => {
+            let token = config.session_token().map_err(|e| e.to_string())?;
+            let url = format!(
+                "{}/{}/leaderboard/private/view/{}.json",
+                config.base_url, config.year, id
+            );
+            let json = fetch::get(&url, &token)?;
+            (url, json)
+        }
+        (None, None) => unreachable!("clap requires a file or an id"),
+    };
+    let export = serde_json::from_str::<Export>(&json).map_err(|e| format!("{}: {}", source, e))?;
+    let year = export
+        .event
+        .parse::<i32>()
+        .map_err(|_| format!("{}: event {:?} is not a year", source, export.event))?;
+
+    let standings = standings(&export);
+    print_table(&standings, year);
+    if let Some(path) = &args.csv {
+        fs::write(path, to_csv(&standings)).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(true)
+}
+
+/// Members in leaderboard order, scored the way private leaderboards are: for each
+/// star, one point per member minus the number of members who earned it first.
+fn standings(export: &Export) -> Vec<Standing> {
+    let members = export.members.values().collect::<Vec<_>>();
+    let mut standings = members
+        .iter()
+        .map(|m| Standing {
+            id: m.id,
+            name: m
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("(anonymous user #{})", m.id)),
+            local_score: 0,
+            stars: m
+                .completion_day_level
+                .iter()
+                .map(|(&day, parts)| {
+                    let ts = |part| parts.get(&part).map(|s: &Star| s.get_star_ts);
+                    (day, [ts(1), ts(2)])
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    let days = members
+        .iter()
+        .flat_map(|m| m.completion_day_level.keys().copied())
+        .collect::<BTreeSet<_>>();
+    for day in days {
+        for part in 1..=2 {
+            let mut earned = members
+                .iter()
+                .enumerate()
+                .filter_map(|(i, m)| {
+                    let star = m.completion_day_level.get(&day)?.get(&part)?;
+                    Some(((star.get_star_ts, star.star_index, m.id), i))
+                })
+                .collect::<Vec<_>>();
+            earned.sort();
+            for (rank, (_, i)) in earned.into_iter().enumerate() {
+                standings[i].local_score += members.len() - rank;
+            }
+        }
+    }
+
+    standings.sort_by_key(|s| {
+        (
+            std::cmp::Reverse(s.local_score),
+            s.last_star().unwrap_or(i64::MAX),
+            s.id,
+        )
+    });
+    standings
+}
+
+/// When the puzzle of `day` in December of `year` unlocked, as a Unix timestamp.
+fn unlock_time(year: i32, day: u8) -> i64 {
+    days_from_civil(year, 12, u32::from(day)) * 24 * 60 * 60 + UNLOCK_OFFSET
+}
+
+/// Days from 1970-01-01 to the given date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Formats a number of seconds as `h:mm:ss`, with days in front once over a day.
+fn format_duration(seconds: i64) -> String {
+    let (days, rest) = (seconds / 86_400, seconds % 86_400);
+    let clock = format!("{}:{:02}:{:02}", rest / 3600, rest / 60 % 60, rest % 60);
+    match days {
+        0 => clock,
+        _ => format!("{}d {}", days, clock),
+    }
+}
+
+fn print_table(standings: &[Standing], year: i32) {
+    let width = standings
+        .iter()
+        .map(|s| s.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    println!("  #  Score  Stars  Name");
+    for (rank, s) in standings.iter().enumerate() {
+        println!(
+            "{:>3}  {:>5}  {:>5}  {}",
+            rank + 1,
+            s.local_score,
+            s.star_count(),
+            s.name
+        );
+    }
+
+    // Solve times per day, counted from when the puzzle unlocked
+    let days = standings
+        .iter()
+        .flat_map(|s| s.stars.keys().copied())
+        .collect::<BTreeSet<_>>();
+    for day in days {
+        println!("\nDay {}", day);
+        println!(
+            "  {:<width$}  {:>12}  {:>12}  {:>12}",
+            "Name", "Part 1", "Part 2", "Between"
+        );
+        let unlock = unlock_time(year, day);
+        for s in standings {
+            let Some(&[part1, part2]) = s.stars.get(&day) else {
+                continue;
+            };
+            let since_unlock = |ts: Option<i64>| {
+                ts.map_or_else(|| String::from("-"), |ts| format_duration(ts - unlock))
+            };
+            let between = match (part1, part2) {
+                (Some(part1), Some(part2)) => format_duration(part2 - part1),
+                _ => String::from("-"),
+            };
+            println!(
+                "  {:<width$}  {:>12}  {:>12}  {:>12}",
+                s.name,
+                since_unlock(part1),
+                since_unlock(part2),
+                between
+            );
+        }
+    }
+}
+
+/// One row per member and day they earned a star on, with Unix timestamps.
+fn to_csv(standings: &[Standing]) -> String {
+    let mut csv =
+        String::from("rank,id,name,local_score,day,part1_ts,part2_ts,seconds_between_parts\n");
+    let field = |ts: Option<i64>| ts.map(|ts| ts.to_string()).unwrap_or_default();
+    for (rank, s) in standings.iter().enumerate() {
+        for (day, &[part1, part2]) in &s.stars {
+            let between = part1.zip(part2).map(|(part1, part2)| part2 - part1);
+            csv += &format!(
+                "{},{},{},{},{},{},{},{}\n",
+                rank + 1,
+                s.id,
+                csv_escape(&s.name),
+                s.local_score,
+                day,
+                field(part1),
+                field(part2),
+                field(between)
+            );
+        }
+    }
+    csv
+}
+
+fn csv_escape(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => String::from(field),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "event": "2022",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "Ada", "stars": 3, "local_score": 0, "last_star_ts": 1669957300,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1669871000, "star_index": 10},
+                            "2": {"get_star_ts": 1669871600, "star_index": 30}},
+                      "2": {"1": {"get_star_ts": 1669957300, "star_index": 50}}}},
+            "2": {"id": 2, "name": null, "stars": 2, "local_score": 0, "last_star_ts": 1669871300,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1669871000, "star_index": 5},
+                            "2": {"get_star_ts": 1669871300, "star_index": 20}}}},
+            "3": {"id": 3, "name": "Bo, Jr.", "stars": 0, "local_score": 0, "last_star_ts": 0,
+                  "completion_day_level": {}}
+        }
+    }"#;
+
+    #[test]
+    fn scores_and_csv() {
+        let export = serde_json::from_str::<Export>(EXPORT).unwrap();
+        let standings = standings(&export);
+
+        // Member 2 wins both stars of day 1 and member 1 is alone on day 2
+        let scores = standings
+            .iter()
+            .map(|s| (s.name.as_str(), s.local_score, s.star_count()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            scores,
+            [
+                ("Ada", 2 + 2 + 3, 3),
+                ("(anonymous user #2)", 3 + 3, 2),
+                ("Bo, Jr.", 0, 0)
+            ]
+        );
+        assert_eq!(
+            to_csv(&standings),
+            "rank,id,name,local_score,day,part1_ts,part2_ts,seconds_between_parts\n\
+             1,1,Ada,7,1,1669871000,1669871600,600\n\
+             1,1,Ada,7,2,1669957300,,\n\
+             2,2,(anonymous user #2),6,1,1669871000,1669871300,300\n"
+        );
+    }
+
+    #[test]
+    fn unlock_times() {
+        assert_eq!(unlock_time(2022, 1), 1_669_870_800);
+        assert_eq!(unlock_time(2015, 25), 1_451_019_600);
+        assert_eq!(format_duration(200), "0:03:20");
+        assert_eq!(format_duration(90_061), "1d 1:01:01");
+    }
+}
@@ -0,0 +1,79 @@
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use util::CountingAlloc;
+
+mod cache;
+mod compare;
+mod config;
+mod fetch;
+mod inspect;
+mod leaderboard;
+mod new;
+mod parse;
+mod run;
+mod sandbox;
+mod serve;
+mod tui;
+
+/// Only counts once enabled, e.g. by `aoc run --alloc`.
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code solution runner")]
+struct Cli {
+    #[command(flatten)]
+    config: config::ConfigArgs,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a day against its input, or against every input in a directory
+    Run(run::RunArgs),
+    /// Download a day's input using the session cookie
+    Fetch(fetch::FetchArgs),
+    /// Create a crate for a new day from the template
+    New(new::NewArgs),
+    /// Serve the solvers over HTTP
+    Serve(serve::ServeArgs),
+    /// Show the standings of a private leaderboard
+    Leaderboard(leaderboard::LeaderboardArgs),
+    /// Check a day's solution variants agree and compare their timings
+    Compare(compare::CompareArgs),
+    /// Print a day's parsed input, to check its parser by
+    Parse(parse::ParseArgs),
+    /// Describe an input's format, to choose a new day's types and parsers by
+    Inspect(inspect::InspectArgs),
+    /// Manage the answers cached by `aoc run`
+    Cache(cache::CacheArgs),
+    /// Browse the days and run them from a full-screen dashboard
+    Tui(tui::TuiArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = cli.config.load().and_then(|config| match cli.command {
+        Command::Run(args) => run::run(args, &config),
+        Command::Fetch(args) => fetch::fetch(args, &config),
+        Command::New(args) => new::new(args, &config),
+        Command::Serve(args) => serve::serve(args),
+        Command::Leaderboard(args) => leaderboard::leaderboard(args, &config),
+        Command::Compare(args) => compare::compare(args, &config),
+        Command::Parse(args) => parse::parse(args, &config),
+        Command::Inspect(args) => inspect::inspect(args, &config),
+        Command::Cache(args) => cache::cache(args, &config),
+        Command::Tui(args) => tui::tui(args, &config),
+    });
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
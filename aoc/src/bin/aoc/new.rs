@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::Path;
+
+use clap::Args;
+use util::Config;
+
+#[derive(Args)]
+pub struct NewArgs {
+    /// Day to create a crate for
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+}
+
+/// Copies the template crate into the day's directory and adds it to the workspace.
+pub fn new(args: NewArgs, config: &Config) -> Result<bool, String> {
+    let dir = config.day_dir(config.year, args.day);
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()));
+    }
+    let member = dir
+        .strip_prefix(&config.root)
+        .map_err(|_| format!("{} is outside the workspace", dir.display()))?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    // `y2023/day01` becomes the crate `y2023_day01`
+    let name = config
+        .day_crate(config.year, args.day)
+        .map_err(|e| e.to_string())?;
+    // The template sits one level below the workspace root
+    let to_root = "../".repeat(member.len());
+
+    copy_template(&config.template_dir, &dir, &|text| {
+        text.replace("name = TODO", &format!("name = {:?}", name))
+            .replace("path = \"../", &format!("path = \"{}", to_root))
+            .replace("TODO::", &format!("{}::", name))
+            .replace("# Day TODO", &format!("# Day {}", args.day))
+            .replace("day = TODO", &format!("day = {}", args.day))
+    })?;
+    add_workspace_member(&config.root, &member.join("/"))?;
+
+    println!("Created {} in {}", name, dir.display());
+    // The runner builds a day in with its feature, finding its crate by name
+    let feature = format!("y{}-day{}", config.year, args.day);
+    println!("To run it with `aoc run`, add it to aoc/Cargo.toml:");
+    println!();
+    println!("[dependencies]");
+    println!(
+        "{} = {{ path = \"../{}\", optional = true }}",
+        name,
+        member.join("/")
+    );
+    println!();
+    println!("[features]");
+    println!("{} = [\"dep:{}\"]", feature, name);
+    println!();
+    println!("and list {:?} in `all-days`", feature);
+    Ok(true)
+}
+
+fn copy_template(from: &Path, to: &Path, fill: &dyn Fn(&str) -> String) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("{}: {}", to.display(), e))?;
+    let entries = fs::read_dir(from).map_err(|e| format!("{}: {}", from.display(), e))?;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("{}: {}", from.display(), e))?;
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_template(&entry.path(), &target, fill)?;
+        } else {
+            let text = fs::read_to_string(entry.path())
+                .map_err(|e| format!("{}: {}", entry.path().display(), e))?;
+            fs::write(&target, fill(&text)).map_err(|e| format!("{}: {}", target.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds `member` to the `members` list of the workspace manifest in `root`.
+fn add_workspace_member(root: &Path, member: &str) -> Result<(), String> {
+    let manifest = root.join("Cargo.toml");
+    let text =
+        fs::read_to_string(&manifest).map_err(|e| format!("{}: {}", manifest.display(), e))?;
+
+    let start = text
+        .find("members = [")
+        .ok_or_else(|| format!("{}: no workspace members list", manifest.display()))?;
+    let end = start
+        + text[start..]
+            .find(']')
+            .ok_or_else(|| format!("{}: unterminated members list", manifest.display()))?;
+    let updated = format!("{}    {:?},\n{}", &text[..end], member, &text[end..]);
+    fs::write(&manifest, updated).map_err(|e| format!("{}: {}", manifest.display(), e))
+}
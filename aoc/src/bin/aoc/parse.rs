@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use util::{Config, DumpFormat};
+
+use crate::run::read_input;
+
+#[derive(Args)]
+pub struct ParseArgs {
+    /// Day whose parser to run
+    #[arg(long)]
+    day: u8,
+    /// Input file [default: the day's `input_path` from aoc.toml]
+    #[arg(long)]
+    input: Option<PathBuf>,
+    /// Format to print the parsed model in: json or ron
+    #[arg(long, default_value = "json", value_parser = parse_dump_format)]
+    dump: DumpFormat,
+}
+
+/// Parses the day's input and prints the model its parts are solved from.
+pub fn parse(args: ParseArgs, config: &Config) -> Result<bool, String> {
+    let year = config.year;
+    let day = aoc::day(year, args.day)
+        .ok_or_else(|| format!("day {} of {} is not solved yet", args.day, year))?;
+    let own_input = args.input.is_none();
+    let path = args
+        .input
+        .unwrap_or_else(|| config.input_path(year, day.day));
+    let input = read_input(day, &path, own_input)?;
+    let model = day
+        .parse(day.lines(&input))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    println!("{}", day.dump(&*model, args.dump)?);
+    Ok(true)
+}
+
+fn parse_dump_format(s: &str) -> Result<DumpFormat, String> {
+    s.parse()
+}
@@ -0,0 +1,723 @@
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::sandbox::{self, Exit, Limits};
+use clap::Args;
+use serde_json::{json, Value};
+use util::{
+    panic_message, AllocStats, Answer, AnswerCache, CacheKey, Config, CountingAlloc, Ledger,
+    OutputFormat, SpanRecord, Spans,
+};
+
+#[derive(Args)]
+pub struct RunArgs {
+    /// Day to run
+    #[arg(long, required_unless_present = "all")]
+    day: Option<u8>,
+    /// Run every solved day against its input, skipping days without one
+    #[arg(long, conflicts_with_all = ["day", "input", "inputs"])]
+    all: bool,
+    /// Only run this part
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Input file [default: the day's `input_path` from aoc.toml]
+    #[arg(long, conflicts_with = "inputs")]
+    input: Option<PathBuf>,
+    /// Directory of inputs to run. Expected answers are read from
+    /// `<name>.answer1` and `<name>.answer2` next to each input, when present.
+    #[arg(long)]
+    inputs: Option<PathBuf>,
+    /// Report allocations, bytes allocated and peak live bytes for each phase
+    #[arg(long)]
+    alloc: bool,
+    /// Break each phase's time down by the spans its solver marks
+    #[arg(long)]
+    spans: bool,
+    /// Write each phase and the spans it marks to this file as Chrome
+    /// trace-event JSON
+    #[arg(long, value_name = "PATH", conflicts_with = "sandbox")]
+    trace: Option<PathBuf>,
+    /// Fail any part whose parse and solve time exceeds the day's budget
+    #[arg(long)]
+    budget: bool,
+    /// Record answers of the day's own input that have no expected answer yet
+    /// in the year's answer ledger
+    #[arg(long, conflicts_with_all = ["input", "inputs"])]
+    save: bool,
+    /// Solve every part instead of using answers cached by an earlier run.
+    /// Cached answers are never used when measuring with --alloc, --spans,
+    /// --trace or --budget.
+    #[arg(long)]
+    no_cache: bool,
+    /// Run the day with the plugin of this name from `plugin_dir` instead of
+    /// the built-in solution
+    #[arg(long, requires = "day")]
+    plugin: Option<String>,
+    /// Run each day in a child process, failing it if it runs out of time or
+    /// memory instead of waiting on it
+    #[arg(long)]
+    sandbox: bool,
+    /// Seconds a sandboxed day may run for
+    #[arg(long, default_value_t = 60, requires = "sandbox", value_name = "SECS")]
+    timeout: u64,
+    /// Address space a sandboxed day may use, in MiB. Only enforced on Unix.
+    #[arg(long, default_value_t = 4096, requires = "sandbox", value_name = "MIB")]
+    memory_limit: u64,
+}
+
+/// Extensions of the sidecar files holding expected answers, by part.
+const ANSWER_EXTENSIONS: [&str; 2] = ["answer1", "answer2"];
+
+pub enum Outcome {
+    /// No expected answer to check against.
+    Unchecked,
+    Pass,
+    Fail(Answer),
+    Panicked(String),
+}
+
+/// What every day of a run shares.
+struct Run<'a> {
+    args: &'a RunArgs,
+    text: bool,
+    /// Whether each day runs against its own input, rather than given ones.
+    own_input: bool,
+    /// Where answers are cached, unless caching is off.
+    cache: Option<AnswerCache>,
+    /// Whether cached answers are used rather than only stored.
+    use_cached: bool,
+}
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    failed: usize,
+    unchecked: usize,
+    over_budget: usize,
+}
+
+/// What running one phase cost.
+pub struct Cost {
+    pub start: Instant,
+    pub elapsed: Duration,
+    pub alloc: AllocStats,
+    /// Spans the phase marked, if recording them.
+    pub spans: Vec<SpanRecord>,
+}
+
+/// Runs `f`, catching panics and recording its time, allocations and spans.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (thread::Result<T>, Cost) {
+    let start = Instant::now();
+    let ((result, spans), alloc) =
+        CountingAlloc::measure(|| Spans::record(|| panic::catch_unwind(AssertUnwindSafe(f))));
+    let cost = Cost {
+        start,
+        elapsed: start.elapsed(),
+        alloc,
+        spans,
+    };
+    (result, cost)
+}
+
+/// When the run started, which trace timestamps count from.
+static RUN_START: OnceLock<Instant> = OnceLock::new();
+
+fn since_run_start(instant: Instant) -> u64 {
+    let start = *RUN_START.get_or_init(Instant::now);
+    instant.saturating_duration_since(start).as_nanos() as u64
+}
+
+/// Loads the plugins in the configured `plugin_dir`, warning about each library
+/// that fails to load rather than giving up on the others.
+pub fn load_plugins(config: &Config) -> Vec<&'static aoc::Plugin> {
+    let (plugins, errors) = aoc::load_plugins(&config.plugin_dir);
+    for error in errors {
+        eprintln!("warning: skipped plugin {}", error);
+    }
+    plugins
+}
+
+/// The year's built-in days, with the day of a plugin where there is none.
+pub fn with_plugin_days(year: u16, plugin_days: &[aoc::Day]) -> Vec<&aoc::Day> {
+    let mut days = aoc::days(year).collect::<Vec<_>>();
+    for day in plugin_days {
+        if !days.iter().any(|d| d.day == day.day) {
+            days.push(day);
+        }
+    }
+    days.sort_by_key(|day| day.day);
+    days
+}
+
+/// Runs the day against each input, or every day against its input, returning
+/// whether every checked answer matched and every budget was met.
+pub fn run(args: RunArgs, config: &Config) -> Result<bool, String> {
+    let year = config.year;
+    RUN_START.get_or_init(Instant::now);
+    // Only needed to run a plugin, or a day without a built-in solution
+    let plugins = match (args.day, &args.plugin) {
+        (Some(n), None) if aoc::day(year, n).is_some() => vec![],
+        _ => load_plugins(config),
+    };
+    let plugin_days = plugins
+        .iter()
+        .map(|plugin| plugin.day())
+        .filter(|day| day.year == year)
+        .collect::<Vec<_>>();
+    // A child of a sandboxed run only runs the day it was given
+    let child = sandbox::child().and_then(|(job, report)| Some((job.parse::<u8>().ok()?, report)));
+    let mut days = match (args.day, &args.plugin) {
+        (Some(n), Some(name)) => {
+            let day = plugin_days.iter().find(|day| match day.code {
+                aoc::Code::Plugin(plugin) => plugin.name == *name,
+                aoc::Code::Builtin { .. } => false,
+            });
+            let day = day.ok_or_else(|| {
+                let names = plugins.iter().map(|p| p.name.as_str()).collect::<Vec<_>>();
+                format!(
+                    "no plugin {:?} in {}, found: {}",
+                    name,
+                    config.plugin_dir.display(),
+                    if names.is_empty() {
+                        String::from("none")
+                    } else {
+                        names.join(", ")
+                    }
+                )
+            })?;
+            if day.day != n {
+                return Err(format!(
+                    "plugin {:?} solves day {}, not {}",
+                    name, day.day, n
+                ));
+            }
+            vec![day]
+        }
+        // Days without a built-in solution run with a plugin solving them
+        (Some(n), None) => vec![aoc::day(year, n)
+            .or_else(|| plugin_days.iter().find(|day| day.day == n))
+            .ok_or_else(|| format!("day {} of {} is not solved yet", n, year))?],
+        (None, _) => with_plugin_days(year, &plugin_days),
+    };
+    if let Some((n, _)) = &child {
+        days.retain(|day| day.day == *n);
+    }
+    let sandbox = (args.sandbox && child.is_none()).then_some(Limits {
+        timeout: Duration::from_secs(args.timeout),
+        memory_mib: args.memory_limit,
+    });
+    let text = match args.day {
+        Some(n) => config.output_format(year, n),
+        None => config.output_format,
+    } == OutputFormat::Text;
+    let mut ledger = Ledger::load(config.answers_path(year)).map_err(|e| e.to_string())?;
+    if args.alloc {
+        CountingAlloc::enable();
+    }
+    if args.spans || args.trace.is_some() {
+        Spans::enable();
+    }
+
+    let run = Run {
+        args: &args,
+        text,
+        // Answers of the day's own input are checked against the ledger
+        own_input: args.input.is_none() && args.inputs.is_none(),
+        cache: (!args.no_cache).then(|| AnswerCache::new(config.answer_cache_dir(year))),
+        use_cached: !(args.alloc || args.spans || args.trace.is_some() || args.budget),
+    };
+
+    // Report panics as failed parts rather than printing them as they happen
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut summary = Summary::default();
+    let mut reports = Vec::new();
+    for day in days {
+        let inputs = match (&args.input, &args.inputs) {
+            (_, Some(dir)) => input_files(dir)?,
+            (Some(input), None) => vec![input.clone()],
+            (None, None) => {
+                let path = config.input_path(year, day.day);
+                if args.all && !path.exists() && day.embedded_input().is_none() {
+                    if text {
+                        println!(
+                            "{} day {}, no input at {}, skipped",
+                            year,
+                            day.day,
+                            path.display()
+                        );
+                    }
+                    continue;
+                }
+                vec![path]
+            }
+        };
+        if let Some(limits) = sandbox {
+            run_sandboxed(day, limits, text, &mut summary, &mut reports)?;
+            continue;
+        }
+        let ledger = run.own_input.then_some(&mut ledger);
+        run_day(day, inputs, ledger, &run, &mut summary, &mut reports)?;
+    }
+    let _ = panic::take_hook();
+
+    // Sandboxed days save the answers they record themselves
+    if args.save && sandbox.is_none() {
+        ledger
+            .save()
+            .map_err(|e| format!("{}: {}", config.answers_path(year).display(), e))?;
+    }
+    if let Some(path) = &args.trace {
+        let events = reports.iter().flat_map(trace_events).collect::<Vec<_>>();
+        let trace = json!({ "traceEvents": events, "displayTimeUnit": "ns" });
+        fs::write(path, serde_json::to_string(&trace).unwrap())
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+
+    let output = json!({
+        "inputs": reports,
+        "passed": summary.passed,
+        "failed": summary.failed,
+        "unchecked": summary.unchecked,
+        "over_budget": summary.over_budget,
+    });
+    if let Some((_, path)) = child {
+        fs::write(&path, output.to_string()).map_err(|e| format!("{}: {}", path.display(), e))?;
+    } else if text {
+        print!(
+            "\n{} passed, {} failed, {} without an expected answer",
+            summary.passed, summary.failed, summary.unchecked
+        );
+        if args.budget {
+            print!(", {} over budget", summary.over_budget);
+        }
+        println!();
+    } else {
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    }
+    Ok(summary.failed == 0 && summary.over_budget == 0)
+}
+
+/// Runs one day against each of its inputs, adding the outcomes to `summary`.
+/// Answers without a sidecar file are checked against `ledger` when given.
+fn run_day(
+    day: &aoc::Day,
+    inputs: Vec<PathBuf>,
+    mut ledger: Option<&mut Ledger>,
+    run: &Run,
+    summary: &mut Summary,
+    reports: &mut Vec<Value>,
+) -> Result<(), String> {
+    let (args, text) = (run.args, run.text);
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+    let budget = day.build_budget();
+
+    for path in inputs {
+        let input = read_input(day, &path, run.own_input)?;
+        let embedded = run.own_input && day.embedded_input().is_some();
+        let source = match embedded {
+            true => format!("{} (embedded)", path.display()),
+            false => path.display().to_string(),
+        };
+        if text {
+            match day.code {
+                aoc::Code::Plugin(plugin) => println!(
+                    "{} day {}, {}, plugin {}",
+                    day.year, day.day, source, plugin.name
+                ),
+                aoc::Code::Builtin { .. } => println!("{} day {}, {}", day.year, day.day, source),
+            }
+        }
+        let mut report = json!({
+            "year": day.year,
+            "day": day.day,
+            "input": path.display().to_string(),
+            "embedded": embedded,
+            "parts": [],
+        });
+
+        let input_hash = util::input_hash(&input);
+        let keys = parts.iter().map(|&part| {
+            day.version().map(|solver| CacheKey {
+                day: day.day,
+                part,
+                input: input_hash,
+                solver,
+            })
+        });
+        let keys = keys.collect::<Vec<_>>();
+        let cached = keys
+            .iter()
+            .map(|key| match (&run.cache, key, run.use_cached) {
+                (Some(cache), Some(key), true) => cache.get(key),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // Both parts are solved against the one parsed model, which isn't
+        // needed when their answers are cached
+        let (model, parse_elapsed) = if cached.iter().all(Option::is_some) {
+            report["parse"] = json!({ "skipped": true });
+            if text {
+                println!("  parse: skipped, answers cached");
+            }
+            (None, Duration::ZERO)
+        } else {
+            let (parsed, parse_cost) = parse_input(day, &input);
+            let (model, parse_error) = match parsed {
+                Ok(model) => (Some(model), None),
+                Err(e) => (None, Some(e)),
+            };
+            report["parse"] = json_phase(&parse_cost, args.alloc);
+            report["parse"]["error"] = json!(parse_error);
+            if text {
+                println!(
+                    "  parse: {} ({})",
+                    parse_error.as_deref().unwrap_or("ok"),
+                    format_cost(parse_cost.elapsed, args.alloc.then_some(parse_cost.alloc))
+                );
+                if args.spans {
+                    print_spans(&parse_cost.spans, parse_cost.elapsed);
+                }
+            }
+            let Some(model) = model else {
+                summary.failed += 1;
+                reports.push(report);
+                continue;
+            };
+            (Some(model), parse_cost.elapsed)
+        };
+
+        for ((&part, key), cached) in parts.iter().zip(&keys).zip(cached) {
+            let (solved, solve_cost) = match (cached, &model) {
+                (Some(answer), _) => (Ok(answer), None),
+                (None, Some(model)) => {
+                    let (solved, cost) = measure(|| day.solve_model(part, &**model));
+                    if let (Ok(answer), Some(cache), Some(key)) = (&solved, &run.cache, key) {
+                        cache
+                            .set(key, answer)
+                            .map_err(|e| format!("{}: {}", cache.dir().display(), e))?;
+                    }
+                    (solved, Some(cost))
+                }
+                (None, None) => unreachable!("the input is parsed unless every answer is cached"),
+            };
+
+            let (answer, outcome) = check_answer(solved, &path, day.day, part, ledger.as_deref())?;
+            if let (Outcome::Unchecked, Some(answer)) = (&outcome, &answer) {
+                if let (true, Some(ledger)) = (args.save, ledger.as_mut()) {
+                    ledger.set(day.day, part, answer.clone());
+                }
+            }
+            let over_budget = solve_cost
+                .as_ref()
+                .is_some_and(|cost| args.budget && parse_elapsed + cost.elapsed > budget);
+            if text {
+                let mut cost = match &solve_cost {
+                    Some(cost) => format_cost(cost.elapsed, args.alloc.then_some(cost.alloc)),
+                    None => String::from("cached"),
+                };
+                if let (true, Some(solve_cost)) = (over_budget, &solve_cost) {
+                    let total = parse_elapsed + solve_cost.elapsed;
+                    cost += &format!(
+                        ", OVER BUDGET: {:.2?} with parse, {:.2?} allowed",
+                        total, budget
+                    );
+                }
+                print_answer(part, answer.as_ref(), &cost, &outcome);
+                if let (true, Some(solve_cost)) = (args.spans, &solve_cost) {
+                    print_spans(&solve_cost.spans, solve_cost.elapsed);
+                }
+            }
+            let mut part_report = match &solve_cost {
+                Some(cost) => json_phase(cost, args.alloc),
+                None => json!({}),
+            };
+            part_report["cached"] = json!(solve_cost.is_none());
+            part_report["part"] = json!(part);
+            part_report["answer"] = json!(answer.map(|a| a.to_string()));
+            part_report["status"] = json!(match &outcome {
+                Outcome::Unchecked => "unchecked",
+                Outcome::Pass => "pass",
+                Outcome::Fail(_) => "fail",
+                Outcome::Panicked(_) => "panicked",
+            });
+            match &outcome {
+                Outcome::Fail(expected) => part_report["expected"] = json!(expected.to_string()),
+                Outcome::Panicked(message) => part_report["error"] = json!(message),
+                Outcome::Unchecked | Outcome::Pass => {}
+            }
+            if args.budget {
+                part_report["budget_ns"] = json!(budget.as_nanos() as u64);
+                part_report["over_budget"] = json!(over_budget);
+            }
+            report["parts"].as_array_mut().unwrap().push(part_report);
+            if over_budget {
+                summary.over_budget += 1;
+            }
+
+            match outcome {
+                Outcome::Unchecked => summary.unchecked += 1,
+                Outcome::Pass => summary.passed += 1,
+                Outcome::Fail(_) | Outcome::Panicked(_) => summary.failed += 1,
+            }
+        }
+        reports.push(report);
+    }
+    Ok(())
+}
+
+/// Runs the day in a child `aoc run`, which prints its own text output, adding
+/// its outcomes to `summary`. A day that times out, runs out of memory or
+/// crashes counts as one failure.
+fn run_sandboxed(
+    day: &aoc::Day,
+    limits: Limits,
+    text: bool,
+    summary: &mut Summary,
+    reports: &mut Vec<Value>,
+) -> Result<(), String> {
+    let (status, detail) = match sandbox::run(&day.day.to_string(), "", limits)? {
+        Exit::Finished(report) => {
+            let count = |field: &str| report[field].as_u64().unwrap_or_default() as usize;
+            summary.passed += count("passed");
+            summary.failed += count("failed");
+            summary.unchecked += count("unchecked");
+            summary.over_budget += count("over_budget");
+            reports.extend(report["inputs"].as_array().into_iter().flatten().cloned());
+            return Ok(());
+        }
+        Exit::TimedOut => ("timed out", format!(" after {:.2?}", limits.timeout)),
+        Exit::OutOfMemory => (
+            "out of memory",
+            format!(" (limit {} MiB)", limits.memory_mib),
+        ),
+        Exit::Crashed(message) => ("crashed", format!(": {}", message)),
+    };
+    let error = format!("{}{}", status, detail);
+    if text {
+        println!(
+            "{} day {}: {}{}",
+            day.year,
+            day.day,
+            status.to_uppercase(),
+            detail
+        );
+    }
+    summary.failed += 1;
+    reports.push(json!({ "year": day.year, "day": day.day, "error": error }));
+    Ok(())
+}
+
+/// Time and, when counted, allocations and spans of a phase as JSON.
+fn json_phase(cost: &Cost, alloc: bool) -> Value {
+    let mut phase = json!({ "elapsed_ns": cost.elapsed.as_nanos() as u64 });
+    if alloc {
+        phase["alloc"] = json!({
+            "allocations": cost.alloc.allocations,
+            "bytes_allocated": cost.alloc.bytes_allocated,
+            "peak_live_bytes": cost.alloc.peak_live_bytes,
+        });
+    }
+    if Spans::is_enabled() {
+        phase["start_ns"] = json!(since_run_start(cost.start));
+        phase["spans"] = cost
+            .spans
+            .iter()
+            .map(|span| {
+                json!({
+                    "name": span.name,
+                    "depth": span.depth,
+                    "start_ns": since_run_start(span.start),
+                    "elapsed_ns": span.elapsed.as_nanos() as u64,
+                })
+            })
+            .collect();
+    }
+    phase
+}
+
+/// Prints the spans a phase marked as a tree, with their share of its time.
+fn print_spans(spans: &[SpanRecord], phase: Duration) {
+    for span in spans {
+        println!(
+            "    {}{}: {:.2?} ({:.0}%)",
+            "  ".repeat(span.depth),
+            span.name,
+            span.elapsed,
+            100.0 * span.elapsed.as_secs_f64() / phase.as_secs_f64().max(f64::EPSILON)
+        );
+    }
+}
+
+/// Complete events of the parse and each part of an input's report, with the
+/// spans they marked nested inside, in the Chrome trace-event format.
+fn trace_events(report: &Value) -> Vec<Value> {
+    let event = |name: String, phase: &Value| {
+        json!({
+            "name": name,
+            "cat": "aoc",
+            "ph": "X",
+            "ts": phase["start_ns"].as_u64().unwrap_or_default() as f64 / 1000.0,
+            "dur": phase["elapsed_ns"].as_u64().unwrap_or_default() as f64 / 1000.0,
+            "pid": 1,
+            "tid": 1,
+            "args": { "input": report["input"] },
+        })
+    };
+    let day = format!("{} day {}", report["year"], report["day"]);
+    let parts = report["parts"].as_array().into_iter().flatten();
+    let phases = std::iter::once((format!("{} parse", day), &report["parse"]))
+        .chain(parts.map(|part| (format!("{} part {}", day, part["part"]), part)));
+
+    let mut events = vec![];
+    // Skipped and cached phases weren't run
+    for (name, phase) in phases.filter(|(_, phase)| phase.get("elapsed_ns").is_some()) {
+        events.push(event(name, phase));
+        let spans = phase["spans"].as_array().into_iter().flatten();
+        events.extend(
+            spans.map(|span| event(span["name"].as_str().unwrap_or_default().into(), span)),
+        );
+    }
+    events
+}
+
+/// Every input file in `dir`, skipping sidecar answer files and hidden files.
+pub fn input_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let mut inputs = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            let hidden = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            let sidecar = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| ANSWER_EXTENSIONS.contains(&e));
+            !hidden && !sidecar
+        })
+        .collect::<Vec<_>>();
+    inputs.sort();
+    if inputs.is_empty() {
+        return Err(format!("no input files in {}", dir.display()));
+    }
+    Ok(inputs)
+}
+
+/// The input at `path`, or the day's embedded input when `path` is the day's
+/// own input and the build embedded it.
+pub fn read_input(day: &aoc::Day, path: &Path, own_input: bool) -> Result<String, String> {
+    match day.embedded_input() {
+        Some(input) if own_input => Ok(String::from(input)),
+        _ => fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// The expected answer from the input's sidecar file for `part`, if there is one.
+pub fn expected_answer(input: &Path, part: u8) -> Result<Option<Answer>, String> {
+    let path = input.with_extension(ANSWER_EXTENSIONS[usize::from(part) - 1]);
+    match fs::read_to_string(&path) {
+        Ok(stored) => stored
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Parses `input` into the day's model, catching panics. A parse error or
+/// panic comes back as the message to report.
+pub fn parse_input(day: &aoc::Day, input: &str) -> (Result<aoc::Model, String>, Cost) {
+    let lines = day.lines(input);
+    let (parsed, cost) = measure(|| day.parse(lines));
+    let model = match parsed {
+        Ok(Ok(model)) => Ok(model),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(payload) => Err(format!("PANICKED: {}", panic_message(payload))),
+    };
+    (model, cost)
+}
+
+/// The answer solving `part` gave, if it didn't panic, and how it compares
+/// with the expected one: from the input's sidecar file, else the ledger.
+pub fn check_answer(
+    solved: thread::Result<Answer>,
+    input: &Path,
+    day: u8,
+    part: u8,
+    ledger: Option<&Ledger>,
+) -> Result<(Option<Answer>, Outcome), String> {
+    let answer = match solved {
+        Ok(answer) => answer,
+        Err(payload) => return Ok((None, Outcome::Panicked(panic_message(payload)))),
+    };
+    let expected = match expected_answer(input, part)? {
+        Some(expected) => Some(expected),
+        None => ledger.and_then(|ledger| ledger.get(day, part).cloned()),
+    };
+    let outcome = match expected {
+        None => Outcome::Unchecked,
+        Some(expected) if expected == answer => Outcome::Pass,
+        Some(expected) => Outcome::Fail(expected),
+    };
+    Ok((Some(answer), outcome))
+}
+
+fn print_answer(part: u8, answer: Option<&Answer>, cost: &str, outcome: &Outcome) {
+    let status = match outcome {
+        Outcome::Unchecked => String::new(),
+        Outcome::Pass => String::from(" ok"),
+        Outcome::Fail(expected) => format!(" FAILED, expected {}", expected),
+        Outcome::Panicked(message) => format!(" PANICKED: {}", message),
+    };
+    match answer {
+        Some(Answer::Grid(rows)) => {
+            println!("  part {}: ({}){}", part, cost, status);
+            for row in rows {
+                println!("    {}", row);
+            }
+        }
+        Some(answer) => println!("  part {}: {} ({}){}", part, answer, cost, status),
+        None => println!("  part {}: ({}){}", part, cost, status),
+    }
+}
+
+fn format_cost(elapsed: Duration, alloc: Option<AllocStats>) -> String {
+    match alloc {
+        Some(alloc) => format!("{:.2?}, {}", elapsed, format_alloc(&alloc)),
+        None => format!("{:.2?}", elapsed),
+    }
+}
+
+fn format_alloc(alloc: &AllocStats) -> String {
+    format!(
+        "{} allocs, {} allocated, {} peak",
+        alloc.allocations,
+        format_bytes(alloc.bytes_allocated),
+        format_bytes(alloc.peak_live_bytes)
+    )
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match unit {
+        0 => format!("{} B", bytes),
+        _ => format!("{:.1} {}", size, UNITS[unit]),
+    }
+}
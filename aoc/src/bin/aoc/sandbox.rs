@@ -0,0 +1,162 @@
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// Tells a child what to do, and that it is sandboxed: the only day to run
+/// for `aoc run`, or the request to solve for `aoc serve`.
+const JOB_VAR: &str = "AOC_SANDBOX_JOB";
+
+/// Tells a sandboxed child where to write its report for the parent.
+const REPORT_VAR: &str = "AOC_SANDBOX_REPORT";
+
+/// How often a running child is checked on.
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Children started so far, keeping the reports of concurrent ones apart.
+static CHILDREN: AtomicUsize = AtomicUsize::new(0);
+
+/// What a sandboxed day is allowed to use.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub timeout: Duration,
+    /// Address space limit, in MiB. Only enforced on Unix.
+    pub memory_mib: u64,
+}
+
+/// How a sandboxed day's child ended.
+#[derive(Debug, PartialEq)]
+pub enum Exit {
+    /// Ran to the end, with the JSON report of its inputs and summary.
+    Finished(Value),
+    TimedOut,
+    OutOfMemory,
+    /// Died any other way, with the last thing it printed to stderr.
+    Crashed(String),
+}
+
+/// The job and report path a parent gave this process, if it is one of its
+/// sandboxed children.
+pub fn child() -> Option<(String, PathBuf)> {
+    let job = env::var(JOB_VAR).ok()?;
+    Some((job, PathBuf::from(env::var_os(REPORT_VAR)?)))
+}
+
+/// Runs `job` in a child `aoc`, given the same arguments as this one and
+/// `input` on its stdin, killing it once it runs out of time.
+pub fn run(job: &str, input: &str, limits: Limits) -> Result<Exit, String> {
+    let exe = env::current_exe().map_err(|e| format!("could not find aoc itself: {}", e))?;
+    let n = CHILDREN.fetch_add(1, Ordering::Relaxed);
+    let report = env::temp_dir().join(format!("aoc-sandbox-{}-{}.json", process::id(), n));
+    let mut command = Command::new(&exe);
+    command
+        .args(env::args_os().skip(1))
+        .env(JOB_VAR, job)
+        .env(REPORT_VAR, &report)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped());
+    limit_memory(&mut command, limits.memory_mib);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("{}: {}", exe.display(), e))?;
+    // Written and read as it goes so neither side stalls on a full pipe
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = String::from(input);
+    thread::spawn(move || {
+        // Fails if the child exits without reading it all, which it may
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let stderr = thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait().map_err(|e| e.to_string())? {
+            Some(status) => break Some(status),
+            None if start.elapsed() >= limits.timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            None => thread::sleep(POLL_INTERVAL),
+        }
+    };
+    let stderr = stderr.join().unwrap_or_default();
+    let report_json = fs::read_to_string(&report).ok();
+    let _ = fs::remove_file(&report);
+
+    Ok(match status {
+        None => Exit::TimedOut,
+        Some(_) => classify(report_json, &stderr),
+    })
+}
+
+/// How a child that exited on its own ended, from its report and stderr.
+fn classify(report: Option<String>, stderr: &str) -> Exit {
+    if let Some(report) = report.and_then(|r| serde_json::from_str(&r).ok()) {
+        return Exit::Finished(report);
+    }
+    // Printed by the default allocation error handler before aborting
+    if stderr.contains("memory allocation of") {
+        return Exit::OutOfMemory;
+    }
+    let last_line = stderr.lines().rev().find(|line| !line.trim().is_empty());
+    Exit::Crashed(String::from(last_line.unwrap_or("no output").trim()))
+}
+
+#[cfg(unix)]
+fn limit_memory(command: &mut Command, memory_mib: u64) {
+    use std::os::unix::process::CommandExt;
+
+    let bytes = memory_mib.saturating_mul(1024 * 1024) as libc::rlim_t;
+    // Only calls setrlimit, which is safe between fork and exec
+    unsafe {
+        command.pre_exec(move || {
+            let limit = libc::rlimit {
+                rlim_cur: bytes,
+                rlim_max: bytes,
+            };
+            match libc::setrlimit(libc::RLIMIT_AS, &limit) {
+                0 => Ok(()),
+                _ => Err(std::io::Error::last_os_error()),
+            }
+        });
+    }
+}
+
+#[cfg(not(unix))]
+fn limit_memory(_command: &mut Command, _memory_mib: u64) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn exits() {
+        assert_eq!(
+            classify(Some(String::from(r#"{"failed": 0}"#)), ""),
+            Exit::Finished(json!({ "failed": 0 }))
+        );
+        assert_eq!(
+            classify(None, "memory allocation of 4294967296 bytes failed\n"),
+            Exit::OutOfMemory
+        );
+        assert_eq!(
+            classify(None, "\nthread 'main' has overflowed its stack\n\n"),
+            Exit::Crashed(String::from("thread 'main' has overflowed its stack"))
+        );
+        assert_eq!(classify(None, ""), Exit::Crashed(String::from("no output")));
+    }
+}
@@ -0,0 +1,216 @@
+use std::fs;
+use std::io::{self, Read};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+use util::panic_message;
+
+use crate::sandbox::{self, Exit};
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8022")]
+    addr: String,
+    /// Largest input accepted, in bytes
+    #[arg(long, default_value_t = 1 << 20)]
+    max_body: usize,
+    /// Seconds a request may spend parsing and solving before it is killed
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+    /// Address space a request may use, in MiB. Only enforced on Unix.
+    #[arg(long, default_value_t = 4096, value_name = "MIB")]
+    memory_limit: u64,
+    /// Requests handled at once, while later ones wait for a free worker
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u16).range(1..))]
+    workers: u16,
+}
+
+/// Limits applied to every request.
+#[derive(Clone, Copy)]
+struct Limits {
+    max_body: usize,
+    solve: sandbox::Limits,
+}
+
+/// Status code and message of a failed request.
+type Failure = (u16, String);
+
+/// Serves `POST /<year>/<day>/<part>` with the input as the body, answering
+/// with the answer and timings as JSON, and `GET /` with the available days.
+/// Each request is solved in a child `aoc serve`, killed once it runs out of
+/// time or memory.
+pub fn serve(args: ServeArgs) -> Result<bool, String> {
+    // A sandboxed child only solves the request it was given
+    if let Some((job, report)) = sandbox::child() {
+        return solve_child(&job, &report);
+    }
+
+    let server = Server::http(&args.addr).map_err(|e| format!("{}: {}", args.addr, e))?;
+    let server = Arc::new(server);
+    let limits = Limits {
+        max_body: args.max_body,
+        solve: sandbox::Limits {
+            timeout: Duration::from_secs(args.timeout),
+            memory_mib: args.memory_limit,
+        },
+    };
+    eprintln!("Listening on http://{}", server.server_addr());
+
+    let workers = (0..args.workers)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    handle(request, limits);
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|payload| format!("worker panicked: {}", panic_message(payload)))?;
+    }
+    Ok(true)
+}
+
+fn handle(mut request: Request, limits: Limits) {
+    let (status, body) = match respond(&mut request, limits) {
+        Ok(body) => (200, body),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+    eprintln!("{} {} -> {}", request.method(), request.url(), status);
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+    let _ = request.respond(response);
+}
+
+/// Routes the request, returning the JSON body of a successful response.
+fn respond(request: &mut Request, limits: Limits) -> Result<Value, Failure> {
+    let url = request.url().to_string();
+    let segments = url.trim_matches('/').split('/').collect::<Vec<_>>();
+    match (request.method(), segments.as_slice()) {
+        (Method::Get, [""]) => {
+            let days = aoc::DAYS
+                .iter()
+                .map(|d| json!({ "year": d.year, "day": d.day }))
+                .collect::<Vec<_>>();
+            Ok(json!({ "days": days }))
+        }
+        (Method::Post, [year, day, part]) => {
+            let (day, part) =
+                find(year, day, part).ok_or_else(|| (404, format!("no solution at {}", url)))?;
+            let input = read_body(request, limits.max_body)?;
+            solve(day, part, &input, limits.solve)
+        }
+        (_, [""] | [_, _, _]) => Err((405, format!("{} is not allowed here", request.method()))),
+        _ => Err((404, format!("no route for {}", url))),
+    }
+}
+
+/// The day and part at `/<year>/<day>/<part>`.
+fn find(year: &str, day: &str, part: &str) -> Option<(&'static aoc::Day, u8)> {
+    let part = part.parse::<u8>().ok().filter(|p| (1..=2).contains(p))?;
+    let day = aoc::day(year.parse().ok()?, day.parse().ok()?)?;
+    Some((day, part))
+}
+
+/// The request body as UTF-8, refusing bodies over `max_body` bytes.
+fn read_body(request: &mut Request, max_body: usize) -> Result<String, Failure> {
+    let too_large = || (413, format!("input is over the {} byte limit", max_body));
+    if request
+        .body_length()
+        .is_some_and(|length| length > max_body)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(max_body as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| (400, e.to_string()))?;
+    if body.len() > max_body {
+        return Err(too_large());
+    }
+    String::from_utf8(body).map_err(|_| (400, String::from("input is not UTF-8")))
+}
+
+/// Parses and solves in a sandboxed child, which is killed after the timeout.
+fn solve(day: &aoc::Day, part: u8, input: &str, limits: sandbox::Limits) -> Result<Value, Failure> {
+    let job = format!("{}/{}/{}", day.year, day.day, part);
+    match sandbox::run(&job, input, limits).map_err(|e| (500, e))? {
+        // A failed request is reported with its status
+        Exit::Finished(report) => match report["error"].as_str() {
+            Some(error) => {
+                let status = report["status"].as_u64().and_then(|s| s.try_into().ok());
+                Err((status.unwrap_or(500), String::from(error)))
+            }
+            None => Ok(report),
+        },
+        Exit::TimedOut => Err((504, format!("no answer within {:?}", limits.timeout))),
+        Exit::OutOfMemory => Err((
+            500,
+            format!("ran out of memory (limit {} MiB)", limits.memory_mib),
+        )),
+        Exit::Crashed(message) => Err((500, format!("crashed: {}", message))),
+    }
+}
+
+/// Solves the request a parent `aoc serve` gave this child, with the input
+/// on stdin, writing the response body or failure to `report`.
+fn solve_child(job: &str, report: &Path) -> Result<bool, String> {
+    let mut input = String::new();
+    let result = match io::stdin().read_to_string(&mut input) {
+        Ok(_) => answer(job, &input),
+        Err(e) => Err((400, e.to_string())),
+    };
+    let body = result.unwrap_or_else(|(status, error)| json!({ "status": status, "error": error }));
+    fs::write(report, body.to_string()).map_err(|e| format!("{}: {}", report.display(), e))?;
+    Ok(true)
+}
+
+/// The answer to the `<year>/<day>/<part>` of `job` with its timings.
+fn answer(job: &str, input: &str) -> Result<Value, Failure> {
+    let segments = job.split('/').collect::<Vec<_>>();
+    let (day, part) = match segments.as_slice() {
+        [year, day, part] => find(year, day, part),
+        _ => None,
+    }
+    .ok_or_else(|| (404, format!("no solution at /{}", job)))?;
+    // Panicking solvers are reported in the response instead
+    panic::set_hook(Box::new(|_| {}));
+
+    let lines = day.lines(input);
+    let start = Instant::now();
+    let parsed = panic::catch_unwind(AssertUnwindSafe(|| day.parse(lines)));
+    let parse_time = start.elapsed();
+    match parsed {
+        Ok(Ok(model)) => {
+            let start = Instant::now();
+            match panic::catch_unwind(AssertUnwindSafe(|| day.solve_model(part, &*model))) {
+                Ok(answer) => Ok(json!({
+                    "year": day.year,
+                    "day": day.day,
+                    "part": part,
+                    "answer": answer,
+                    "parse_ns": parse_time.as_nanos() as u64,
+                    "solve_ns": start.elapsed().as_nanos() as u64,
+                })),
+                Err(payload) => Err((500, format!("solver panicked: {}", panic_message(payload)))),
+            }
+        }
+        Ok(Err(e)) => Err((422, e.to_string())),
+        Err(payload) => Err((500, format!("parser panicked: {}", panic_message(payload)))),
+    }
+}
@@ -0,0 +1,498 @@
+use std::io::{self, Stdout};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Args;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{Frame, Terminal};
+use util::{Answer, Config, Ledger, SpanRecord, Spans};
+
+use crate::run::{self, check_answer, measure, parse_input, read_input, Outcome};
+
+/// How often the dashboard checks whether a running day has finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Args)]
+pub struct TuiArgs {
+    /// Day to select first
+    #[arg(long)]
+    day: Option<u8>,
+}
+
+/// A day listed in the dashboard, with its latest run.
+struct Entry<'a> {
+    day: &'a aoc::Day,
+    input: PathBuf,
+    run: Option<DayRun>,
+}
+
+/// What running a day against its input gave.
+struct DayRun {
+    parse: Phase,
+    /// Why the input couldn't be read or parsed, if it couldn't.
+    error: Option<String>,
+    parts: Vec<PartRun>,
+}
+
+struct PartRun {
+    part: u8,
+    answer: Option<Answer>,
+    outcome: Outcome,
+    phase: Phase,
+}
+
+/// When a phase started and how long it and the spans it marked took.
+struct Phase {
+    start: Instant,
+    elapsed: Duration,
+    spans: Vec<SpanRecord>,
+}
+
+/// How a day's latest run went, as listed next to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    NoInput,
+    NotRun,
+    /// Every answer matched the expected one.
+    Passed,
+    /// Solved, with some answers not checked.
+    Unchecked,
+    Failed,
+}
+
+impl Status {
+    fn label(self) -> Span<'static> {
+        match self {
+            Status::NoInput => "no input".dark_gray(),
+            Status::NotRun => "not run".gray(),
+            Status::Passed => "ok".green(),
+            Status::Unchecked => "solved".cyan(),
+            Status::Failed => "FAILED".red().bold(),
+        }
+    }
+}
+
+impl Entry<'_> {
+    /// Whether there's an input to run against, in a file or embedded.
+    fn has_input(&self) -> bool {
+        self.day.embedded_input().is_some() || self.input.exists()
+    }
+
+    fn status(&self) -> Status {
+        let Some(run) = &self.run else {
+            return match self.has_input() {
+                true => Status::NotRun,
+                false => Status::NoInput,
+            };
+        };
+        if run.error.is_some() {
+            return Status::Failed;
+        }
+        let outcomes = run.parts.iter().map(|part| &part.outcome);
+        outcomes.fold(Status::Passed, |status, outcome| match outcome {
+            Outcome::Fail(_) | Outcome::Panicked(_) => Status::Failed,
+            Outcome::Unchecked if status == Status::Passed => Status::Unchecked,
+            Outcome::Unchecked | Outcome::Pass => status,
+        })
+    }
+}
+
+/// The dashboard's state.
+struct App<'a> {
+    year: u16,
+    entries: Vec<Entry<'a>>,
+    selected: usize,
+    /// Parts the `r` key re-runs.
+    last_parts: Vec<u8>,
+    /// Day being run on a worker thread, shown while it runs, and where the
+    /// worker sends back its run.
+    running: Option<(usize, Receiver<DayRun>)>,
+    ledger: Ledger,
+}
+
+/// Shows every day of the year with the status of its latest run, running the
+/// selected day against its input on a keypress.
+pub fn tui(args: TuiArgs, config: &Config) -> Result<bool, String> {
+    let year = config.year;
+    let plugin_days = run::load_plugins(config)
+        .iter()
+        .map(|plugin| plugin.day())
+        .filter(|day| day.year == year)
+        .collect::<Vec<_>>();
+    // Runs on the worker thread borrow their day for as long as they may hang,
+    // which is as long as the dashboard is up. Plugins are loaded for good too
+    let plugin_days: &'static [aoc::Day] = plugin_days.leak();
+    // Days without a built-in solution run with a plugin solving them
+    let days = run::with_plugin_days(year, plugin_days);
+    if days.is_empty() {
+        return Err(format!("no days of {} are solved yet", year));
+    }
+
+    let entries = days
+        .into_iter()
+        .map(|day| Entry {
+            day,
+            input: config.input_path(year, day.day),
+            run: None,
+        })
+        .collect::<Vec<_>>();
+    let selected = args
+        .day
+        .and_then(|n| entries.iter().position(|e| e.day.day == n))
+        .unwrap_or_default();
+    let mut app = App {
+        year,
+        entries,
+        selected,
+        last_parts: vec![1, 2],
+        running: None,
+        ledger: Ledger::load(config.answers_path(year)).map_err(|e| e.to_string())?,
+    };
+
+    Spans::enable();
+    // Panics are shown as the part's outcome, not printed over the dashboard
+    panic::set_hook(Box::new(|_| {}));
+    let result = Screen::enter().and_then(|mut screen| app.event_loop(&mut screen.terminal));
+    let _ = panic::take_hook();
+    result.map_err(|e| e.to_string())?;
+    Ok(true)
+}
+
+impl App<'static> {
+    /// Handles keys until `q` is pressed, while days run on a worker thread so
+    /// one that hangs leaves the dashboard responsive. Quitting leaves it behind
+    /// to end with the process.
+    fn event_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+        loop {
+            if let Some((index, finished)) = self.running.take() {
+                match finished.try_recv() {
+                    Ok(run) => self.entries[index].run = Some(run),
+                    Err(TryRecvError::Empty) => self.running = Some((index, finished)),
+                    // The worker panicked outside the day's code, leaving no run
+                    Err(TryRecvError::Disconnected) => {}
+                }
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+            if self.running.is_some() && !event::poll(POLL_INTERVAL)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let parts = match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected = self.selected.saturating_sub(1);
+                    continue;
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.selected = (self.selected + 1).min(self.entries.len() - 1);
+                    continue;
+                }
+                KeyCode::Char('1') => vec![1],
+                KeyCode::Char('2') => vec![2],
+                KeyCode::Enter => vec![1, 2],
+                KeyCode::Char('r') => self.last_parts.clone(),
+                _ => continue,
+            };
+            // One day runs at a time
+            if self.running.is_some() {
+                continue;
+            }
+            self.last_parts = parts.clone();
+            let entry = &self.entries[self.selected];
+            let (day, input, ledger) = (entry.day, entry.input.clone(), self.ledger.clone());
+            let (sender, finished) = mpsc::channel();
+            thread::spawn(move || {
+                let _ = sender.send(run_day(day, &input, &parts, &ledger));
+            });
+            self.running = Some((self.selected, finished));
+        }
+    }
+}
+
+impl App<'_> {
+    fn draw(&self, frame: &mut Frame) {
+        let [main, help] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [list, detail] =
+            Layout::horizontal([Constraint::Length(24), Constraint::Min(0)]).areas(main);
+
+        let items = self.entries.iter().enumerate().map(|(i, entry)| {
+            let status = match &self.running {
+                Some((running, _)) if *running == i => "running…".yellow(),
+                _ => entry.status().label(),
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("day {:>2}  ", entry.day.day)),
+                status,
+            ]))
+        });
+        let days = List::new(items)
+            .block(Block::bordered().title(format!(" {} ", self.year)))
+            .highlight_style(Style::new().reversed());
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(days, list, &mut state);
+
+        let entry = &self.entries[self.selected];
+        let title = match entry.day.code {
+            aoc::Code::Plugin(plugin) => format!(" day {}, plugin {} ", entry.day.day, plugin.name),
+            aoc::Code::Builtin { .. } => format!(" day {} ", entry.day.day),
+        };
+        let details = Paragraph::new(detail_lines(entry))
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, detail);
+
+        let keys = " ↑↓ select   1/2 run part   enter run both   r re-run   q quit";
+        frame.render_widget(Line::from(keys.dark_gray()), help);
+    }
+}
+
+/// The input, answers, timings, errors and spans of the entry's latest run.
+fn detail_lines(entry: &Entry) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(match entry.day.embedded_input() {
+            Some(_) => format!("input:  {} (embedded)", entry.input.display()),
+            None => format!("input:  {}", entry.input.display()),
+        }),
+        Line::from(format!("budget: {:.2?}", entry.day.budget)),
+        Line::default(),
+    ];
+    let Some(run) = &entry.run else {
+        lines.push(Line::from(match entry.has_input() {
+            true => "Not run yet".gray(),
+            false => "No input to run against".dark_gray(),
+        }));
+        return lines;
+    };
+
+    match &run.error {
+        Some(error) => lines.push(Line::from(vec![
+            Span::raw("parse: "),
+            Span::raw(error.clone()).red(),
+        ])),
+        None => lines.push(Line::from(format!("parse: ok ({:.2?})", run.parse.elapsed))),
+    }
+    for part in &run.parts {
+        let status = match &part.outcome {
+            Outcome::Unchecked => Span::raw(""),
+            Outcome::Pass => " ok".green(),
+            Outcome::Fail(expected) => format!(" FAILED, expected {}", expected).red(),
+            Outcome::Panicked(message) => format!(" PANICKED: {}", message).red(),
+        };
+        let elapsed = format!("({:.2?})", part.phase.elapsed);
+        match &part.answer {
+            Some(Answer::Grid(rows)) => {
+                let head = format!("part {}: {}", part.part, elapsed);
+                lines.push(Line::from(vec![Span::raw(head), status]));
+                lines.extend(rows.iter().map(|row| Line::from(format!("  {}", row))));
+            }
+            Some(answer) => {
+                let head = format!("part {}: {} {}", part.part, answer, elapsed);
+                lines.push(Line::from(vec![Span::raw(head).bold(), status]));
+            }
+            None => {
+                let head = format!("part {}: {}", part.part, elapsed);
+                lines.push(Line::from(vec![Span::raw(head), status]));
+            }
+        }
+    }
+
+    // Spans each phase marked, timed from the start of the run
+    let phases = std::iter::once((String::from("parse"), &run.parse)).chain(
+        run.parts
+            .iter()
+            .map(|p| (format!("part {}", p.part), &p.phase)),
+    );
+    let phases = phases
+        .filter(|(_, phase)| !phase.spans.is_empty())
+        .collect::<Vec<_>>();
+    if !phases.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from("trace".bold()));
+    }
+    for (name, phase) in phases {
+        let offset = |start: Instant| start.saturating_duration_since(run.parse.start);
+        lines.push(Line::from(format!(
+            "  {}  +{:.2?}  {:.2?}",
+            name,
+            offset(phase.start),
+            phase.elapsed
+        )));
+        for span in &phase.spans {
+            lines.push(Line::from(format!(
+                "    {}{}  +{:.2?}  {:.2?}",
+                "  ".repeat(span.depth),
+                span.name,
+                offset(span.start),
+                span.elapsed
+            )));
+        }
+    }
+    lines
+}
+
+/// Parses the input and solves `parts` of the day, checking answers against
+/// their sidecar files or the ledger. Answers are always solved, not cached.
+fn run_day(day: &aoc::Day, input: &Path, parts: &[u8], ledger: &Ledger) -> DayRun {
+    let mut run = DayRun {
+        parse: Phase {
+            start: Instant::now(),
+            elapsed: Duration::ZERO,
+            spans: vec![],
+        },
+        error: None,
+        parts: vec![],
+    };
+    let text = match read_input(day, input, true) {
+        Ok(text) => text,
+        Err(e) => {
+            run.error = Some(e);
+            return run;
+        }
+    };
+    let (parsed, cost) = parse_input(day, &text);
+    run.parse = Phase {
+        start: cost.start,
+        elapsed: cost.elapsed,
+        spans: cost.spans,
+    };
+    let model = match parsed {
+        Ok(model) => model,
+        Err(e) => {
+            run.error = Some(e);
+            return run;
+        }
+    };
+
+    for &part in parts {
+        let (solved, cost) = measure(|| day.solve_model(part, &*model));
+        let (answer, outcome) = match check_answer(solved, input, day.day, part, Some(ledger)) {
+            Ok(checked) => checked,
+            Err(e) => {
+                run.error = Some(e);
+                return run;
+            }
+        };
+        run.parts.push(PartRun {
+            part,
+            answer,
+            outcome,
+            phase: Phase {
+                start: cost.start,
+                elapsed: cost.elapsed,
+                spans: cost.spans,
+            },
+        });
+    }
+    run
+}
+
+/// The terminal in raw mode on the alternate screen, put back when dropped.
+struct Screen {
+    terminal: Terminal<CrosstermBackend<Stdout>>,
+}
+
+impl Screen {
+    fn enter() -> io::Result<Screen> {
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        let terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+        Ok(Screen { terminal })
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn part(outcome: Outcome) -> PartRun {
+        PartRun {
+            part: 1,
+            answer: None,
+            outcome,
+            phase: Phase {
+                start: Instant::now(),
+                elapsed: Duration::ZERO,
+                spans: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn status() {
+        let Some(day) = aoc::days(2022).next() else {
+            return;
+        };
+        let mut entry = Entry {
+            day,
+            input: PathBuf::from("/nonexistent/input.txt"),
+            run: None,
+        };
+        assert_eq!(entry.status(), Status::NoInput);
+
+        let run = |outcomes: Vec<Outcome>, error: Option<&str>| DayRun {
+            parse: part(Outcome::Pass).phase,
+            error: error.map(String::from),
+            parts: outcomes.into_iter().map(part).collect(),
+        };
+        entry.run = Some(run(vec![Outcome::Pass, Outcome::Pass], None));
+        assert_eq!(entry.status(), Status::Passed);
+        entry.run = Some(run(vec![Outcome::Pass, Outcome::Unchecked], None));
+        assert_eq!(entry.status(), Status::Unchecked);
+        let panicked = Outcome::Panicked(String::from("oops"));
+        entry.run = Some(run(vec![panicked, Outcome::Unchecked], None));
+        assert_eq!(entry.status(), Status::Failed);
+        entry.run = Some(run(vec![], Some("line 1: expected a number")));
+        assert_eq!(entry.status(), Status::Failed);
+    }
+
+    #[cfg(feature = "y2022-day6")]
+    #[test]
+    fn runs_day_on_worker() {
+        let dir = std::env::temp_dir().join(format!("aoc-tui-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("input.txt");
+        std::fs::write(&input, "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n").unwrap();
+        std::fs::write(input.with_extension("answer2"), "20").unwrap();
+        let mut ledger = Ledger::load(dir.join("answers.toml")).unwrap();
+        ledger.set(6, 1, Answer::from(7));
+
+        let day = aoc::day(2022, 6).unwrap();
+        let worker_input = input.clone();
+        let run = thread::spawn(move || run_day(day, &worker_input, &[1, 2], &ledger))
+            .join()
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(run.error, None);
+        assert_eq!(run.parts[0].answer, Some(Answer::from(7)));
+        assert!(matches!(run.parts[0].outcome, Outcome::Pass));
+        // The sidecar file is checked before the ledger
+        assert!(
+            matches!(&run.parts[1].outcome, Outcome::Fail(expected) if *expected == Answer::from(20))
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use std::fmt;
+
+use util::ParseError;
+
+/// Why [`solve`](crate::solve) could not produce an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AocError {
+    /// No solution is registered for the day.
+    UnknownDay { year: u16, day: u8 },
+    /// Parts are numbered 1 and 2.
+    UnknownPart(u8),
+    /// The input does not match the day's format.
+    Parse(ParseError),
+    /// The parser or solver panicked, with this message.
+    Solve(String),
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AocError::UnknownDay { year, day } => {
+                write!(f, "day {} of {} is not solved yet", day, year)
+            }
+            AocError::UnknownPart(part) => write!(f, "there is no part {}, only 1 and 2", part),
+            AocError::Parse(e) => write!(f, "invalid input: {}", e),
+            AocError::Solve(message) => write!(f, "solving panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Parse(e) => Some(e),
+            AocError::UnknownDay { .. } | AocError::UnknownPart(_) | AocError::Solve(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(e: ParseError) -> Self {
+        AocError::Parse(e)
+    }
+}
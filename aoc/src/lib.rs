@@ -0,0 +1,268 @@
+//! Registry of every day's solutions, used by the `aoc` runner and by
+//! programs calling [`solve`] directly.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+use util::Normalize;
+
+mod error;
+mod plugin;
+
+pub use error::AocError;
+pub use plugin::{load_plugins, Plugin, PluginError};
+pub use util::{Answer, DumpFormat, Dumper, Model, ParseError, Parser, Part, Solver};
+
+/// Budgets are set for release builds, so debug builds get this much longer.
+pub const DEBUG_BUDGET_FACTOR: u32 = 10;
+
+/// A day's solutions, along with the normalizations its input format allows.
+pub struct Day {
+    pub year: u16,
+    pub day: u8,
+    pub normalize: Normalize,
+    /// Time allowed for parsing plus either part, in a release build: the
+    /// crate's `TIME_BUDGET`, or `util::DEFAULT_TIME_BUDGET` without one.
+    pub budget: Duration,
+    pub code: Code,
+}
+
+/// Where a day's parser and solvers come from.
+#[derive(Clone, Copy)]
+pub enum Code {
+    /// Compiled into the registry, along with alternative implementations of
+    /// its parts for `aoc compare`.
+    Builtin {
+        parse: Parser,
+        parts: [Solver; 2],
+        /// Prints what `parse` returns, for `aoc parse --dump`.
+        dump: Dumper,
+        variants: &'static [Part],
+        /// Hash of the day crate's sources, which cached answers are keyed by.
+        version: u64,
+    },
+    /// Loaded at runtime.
+    Plugin(&'static Plugin),
+}
+
+impl Day {
+    /// A day of `year` from the parts its crate registered with `#[aoc]`.
+    #[allow(dead_code)] // Unused when built without any days
+    const fn new(year: u16, budget: Duration, [part1, part2]: [Part; 2]) -> Day {
+        assert!(part1.day == part2.day && part1.part == 1 && part2.part == 2);
+        Day {
+            year,
+            day: part1.day,
+            normalize: part1.normalize,
+            budget,
+            code: Code::Builtin {
+                parse: part1.parse,
+                parts: [part1.solve, part2.solve],
+                dump: part1.dump,
+                variants: &[],
+                version: part1.version,
+            },
+        }
+    }
+
+    /// Registers named variants of the day's parts, from `#[aoc(.., variant = ..)]`.
+    #[allow(dead_code)]
+    const fn with_variants(mut self, with: &'static [Part]) -> Day {
+        let mut i = 0;
+        while i < with.len() {
+            assert!(with[i].day == self.day && with[i].variant.is_some());
+            i += 1;
+        }
+        if let Code::Builtin { ref mut variants, .. } = self.code {
+            *variants = with;
+        }
+        self
+    }
+
+    /// The day's budget in this build, i.e. longer in a debug build.
+    pub fn build_budget(&self) -> Duration {
+        match cfg!(debug_assertions) {
+            true => self.budget * DEBUG_BUDGET_FACTOR,
+            false => self.budget,
+        }
+    }
+
+    /// The day's input, if it was embedded in the build with the `embed-input`
+    /// feature.
+    pub fn embedded_input(&self) -> Option<&'static str> {
+        EMBEDDED_INPUTS
+            .iter()
+            .find(|&&(year, day, _)| year == self.year && day == self.day)
+            .map(|&(_, _, input)| input)
+    }
+
+    /// Normalizes the raw `input` and splits it into lines.
+    pub fn lines(&self, input: &str) -> Vec<String> {
+        self.normalize
+            .apply(input)
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    /// Parses lines returned by [`Day::lines`] into the model both parts are solved from.
+    pub fn parse(&self, lines: Vec<String>) -> Result<Model, ParseError> {
+        match self.code {
+            Code::Builtin { parse, .. } => parse(lines.into_iter()),
+            Code::Plugin(plugin) => plugin.parse(lines),
+        }
+    }
+
+    /// Solves `part` (1 or 2) of the puzzle from a model returned by [`Day::parse`].
+    pub fn solve_model(&self, part: u8, model: &dyn Any) -> Answer {
+        match self.code {
+            Code::Builtin { parts, .. } => (parts[usize::from(part) - 1])(model),
+            Code::Plugin(plugin) => plugin.solve(part, model),
+        }
+    }
+
+    /// Prints a model returned by [`Day::parse`] in `format`. Plugins' models
+    /// can't be printed.
+    pub fn dump(&self, model: &dyn Any, format: DumpFormat) -> Result<String, String> {
+        match self.code {
+            Code::Builtin { dump, .. } => dump(model, format),
+            Code::Plugin(plugin) => Err(format!("plugin {} can't dump its model", plugin.name)),
+        }
+    }
+
+    /// Version of the day's solvers to key cached answers by, if they are
+    /// built in. Plugins are not cached.
+    pub fn version(&self) -> Option<u64> {
+        match self.code {
+            Code::Builtin { version, .. } => Some(version),
+            Code::Plugin(_) => None,
+        }
+    }
+
+    /// Normalizes and parses the raw `input` once, then solves both parts.
+    pub fn solve(&self, input: &str) -> Result<[Answer; 2], ParseError> {
+        let model = self.parse(self.lines(input))?;
+        Ok([1, 2].map(|part| self.solve_model(part, &*model)))
+    }
+}
+
+// Inputs embedded by the build script, by year and day
+include!(concat!(env!("OUT_DIR"), "/inputs.rs"));
+
+// Every built-in day, generated by the build script from the solvers each
+// day's crate marks with `#[aoc]`
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+/// Looks up a day of a year in the registry.
+pub fn day(year: u16, day: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.year == year && d.day == day)
+}
+
+/// Every registered day of the year.
+pub fn days(year: u16) -> impl Iterator<Item = &'static Day> {
+    DAYS.iter().filter(move |d| d.year == year)
+}
+
+/// A part of a day that [`solve`] can answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub part: u8,
+}
+
+/// Every part that has a solution, in order.
+pub fn solutions() -> impl Iterator<Item = Solution> {
+    DAYS.iter().flat_map(|d| {
+        (1..=2).map(|part| Solution {
+            year: d.year,
+            day: d.day,
+            part,
+        })
+    })
+}
+
+/// Solves `part` (1 or 2) of the day from its raw puzzle input. A parser or
+/// solver that panics, as some do on input they don't expect, gives
+/// [`AocError::Solve`] rather than unwinding into the caller, though the panic
+/// hook still runs.
+pub fn solve(year: u16, day: u8, part: u8, input: &str) -> Result<Answer, AocError> {
+    let day = self::day(year, day).ok_or(AocError::UnknownDay { year, day })?;
+    if !(1..=2).contains(&part) {
+        return Err(AocError::UnknownPart(part));
+    }
+    let solved = panic::catch_unwind(AssertUnwindSafe(|| {
+        let model = day.parse(day.lines(input))?;
+        Ok(day.solve_model(part, &*model))
+    }));
+    solved.unwrap_or_else(|payload| Err(AocError::Solve(util::panic_message(payload))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(all(feature = "y2022-day1", feature = "y2022-day4", feature = "y2022-day6"))]
+    fn examples() {
+        let examples: [(u8, &str, &str, &str); 3] = [
+            (1, "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n", "24000", "45000"),
+            (4, "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n", "2", "4"),
+            (6, "mjqjpqmgbljsphdztnvjfqwrcgsmlb\r\n", "7", "19"),
+        ];
+        for (n, input, part1, part2) in examples {
+            let answers = day(2022, n).unwrap().solve(input).unwrap();
+            assert_eq!(answers, [part1.parse().unwrap(), part2.parse().unwrap()]);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "y2015-day1")]
+    fn other_years() {
+        assert_eq!(days(2015).map(|d| d.day).collect::<Vec<_>>(), [1]);
+        assert_eq!(solve(2015, 1, 1, "))(((((\n"), Ok(Answer::from(3)));
+        assert_eq!(solve(2015, 1, 2, "()())\n"), Ok(Answer::from(5usize)));
+        assert_eq!(solve(2016, 1, 1, "()"), Err(AocError::UnknownDay { year: 2016, day: 1 }));
+    }
+
+    #[test]
+    #[cfg(feature = "y2022-day4")]
+    fn solve_errors() {
+        assert_eq!(solve(2022, 4, 1, "2-4,6-8\n"), Ok(Answer::from(0u32)));
+        assert_eq!(solve(2022, 26, 1, ""), Err(AocError::UnknownDay { year: 2022, day: 26 }));
+        assert_eq!(solve(2021, 4, 1, ""), Err(AocError::UnknownDay { year: 2021, day: 4 }));
+        assert_eq!(solve(2022, 4, 3, "2-4,6-8\n"), Err(AocError::UnknownPart(3)));
+        assert!(matches!(solve(2022, 4, 1, "2-4\n"), Err(AocError::Parse(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "y2022-day6")]
+    fn solve_catches_panics() {
+        // Parse only checks for the start-of-packet marker that part 1 needs,
+        // so part 2 panics finding no start-of-message marker. The hook is
+        // left alone, as it is shared with the tests running alongside, so the
+        // panic is still printed.
+        let solved = solve(2022, 6, 2, "abcd");
+        assert!(matches!(&solved, Err(AocError::Solve(m)) if m.contains("14 characters")), "{:?}", solved);
+    }
+
+    #[test]
+    #[cfg(feature = "y2022-day4")]
+    fn dump() {
+        let day = day(2022, 4).unwrap();
+        let model = day.parse(day.lines("2-4,6-8\n")).unwrap();
+        let json = day.dump(&*model, DumpFormat::Json).unwrap();
+        assert_eq!(json.split_whitespace().collect::<String>(), r#"[[{"start":2,"end":4},{"start":6,"end":8}]]"#);
+        let ron = day.dump(&*model, DumpFormat::Ron).unwrap();
+        assert_eq!(ron.split_whitespace().collect::<String>(), "[((start:2,end:4,),(start:6,end:8,)),]");
+    }
+
+    #[test]
+    fn solutions_are_registered() {
+        assert_eq!(solutions().count(), DAYS.len() * 2);
+        for s in solutions() {
+            assert!(day(s.year, s.day).is_some());
+        }
+    }
+}
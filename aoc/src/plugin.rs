@@ -0,0 +1,197 @@
+use std::any::Any;
+use std::ffi::{c_char, c_void, CStr};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use libloading::Library;
+use util::{
+    Normalize, PluginEntry, PluginInfo, DEFAULT_TIME_BUDGET, PLUGIN_ABI_VERSION, PLUGIN_ENTRY,
+};
+
+use crate::{Answer, Code, Day, Model, ParseError};
+
+/// A day solution loaded from a `cdylib` exporting `util::export_plugin!`.
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+    /// Points into the library, so is only valid while it stays loaded.
+    info: &'static PluginInfo,
+    _library: Library,
+}
+
+/// Why a plugin library could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginError {
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// A model parsed by a plugin, released by it when dropped.
+struct PluginModel {
+    handle: *mut c_void,
+    free: unsafe extern "C" fn(*mut c_void),
+}
+
+impl Drop for PluginModel {
+    fn drop(&mut self) {
+        unsafe { (self.free)(self.handle) }
+    }
+}
+
+impl Plugin {
+    /// Loads the library at `path` and checks it speaks this version of the ABI.
+    pub fn load(path: &Path) -> Result<Plugin, PluginError> {
+        let error = |message: String| PluginError {
+            path: path.to_path_buf(),
+            message,
+        };
+        // Loading runs the library's initializers, which plugins are trusted with
+        let library = unsafe { Library::new(path) }.map_err(|e| error(e.to_string()))?;
+        let info: Option<&'static PluginInfo> = unsafe {
+            let entry = library
+                .get::<PluginEntry>(PLUGIN_ENTRY.as_bytes())
+                .map_err(|e| error(e.to_string()))?;
+            entry().as_ref()
+        };
+        let info = info.ok_or_else(|| error(format!("{} returned null", PLUGIN_ENTRY)))?;
+        if info.abi_version != PLUGIN_ABI_VERSION {
+            return Err(error(format!(
+                "built for plugin ABI {}, expected {}",
+                info.abi_version, PLUGIN_ABI_VERSION
+            )));
+        }
+        let name = unsafe { CStr::from_ptr(info.name) }
+            .to_string_lossy()
+            .into_owned();
+
+        Ok(Plugin {
+            name,
+            path: path.to_path_buf(),
+            info,
+            _library: library,
+        })
+    }
+
+    /// The day the plugin solves, to run in place of a registered one.
+    pub fn day(&'static self) -> Day {
+        Day {
+            year: self.info.year,
+            day: self.info.day,
+            // The plugin normalizes the input itself
+            normalize: Normalize::NONE,
+            // Plugins have no way to set their own
+            budget: DEFAULT_TIME_BUDGET,
+            code: Code::Plugin(self),
+        }
+    }
+
+    pub(crate) fn parse(&self, lines: Vec<String>) -> Result<Model, ParseError> {
+        let input = lines.join("\n");
+        let mut error = ptr::null_mut();
+        let handle = unsafe { (self.info.parse)(input.as_ptr(), input.len(), &mut error) };
+        if handle.is_null() {
+            return Err(ParseError::new(self.take_string(error)));
+        }
+        Ok(Box::new(PluginModel {
+            handle,
+            free: self.info.free_model,
+        }))
+    }
+
+    /// Solves `part`, panicking like a registered solver would when the plugin fails.
+    pub(crate) fn solve(&self, part: u8, model: &dyn Any) -> Answer {
+        let model = model
+            .downcast_ref::<PluginModel>()
+            .expect("model was parsed by another day");
+        let mut error = ptr::null_mut();
+        let answer = unsafe { (self.info.parts[usize::from(part) - 1])(model.handle, &mut error) };
+        if answer.is_null() {
+            panic!("{}", self.take_string(error));
+        }
+        let answer = self.take_string(answer);
+        answer.parse().unwrap_or_else(|e| {
+            panic!(
+                "{} answered {:?}, which is not an answer: {}",
+                self.name, answer, e
+            )
+        })
+    }
+
+    /// Copies out a string the plugin returned and releases it.
+    fn take_string(&self, string: *mut c_char) -> String {
+        if string.is_null() {
+            return format!("{} failed without a message", self.name);
+        }
+        let text = unsafe { CStr::from_ptr(string) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe { (self.info.free_string)(string) };
+        text
+    }
+}
+
+/// Loads every plugin library in `dir`, if it exists, with why each library
+/// that is not a plugin failed to load. Plugins stay loaded for the rest of
+/// the program, like the days compiled into it.
+pub fn load_plugins(dir: &Path) -> (Vec<&'static Plugin>, Vec<PluginError>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return (vec![], vec![]);
+    };
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|e| e == std::env::consts::DLL_EXTENSION)
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut plugins = vec![];
+    let mut errors = vec![];
+    for path in paths {
+        match Plugin::load(&path) {
+            Ok(plugin) => plugins.push(&*Box::leak(Box::new(plugin))),
+            Err(error) => errors.push(error),
+        }
+    }
+    (plugins, errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_errors() {
+        let dir = std::env::temp_dir().join(format!("aoc-plugins-{}", std::process::id()));
+        let (plugins, errors) = load_plugins(&dir);
+        assert!(plugins.is_empty() && errors.is_empty());
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("notes.txt"), "not a plugin").unwrap();
+        let (plugins, errors) = load_plugins(&dir);
+        assert!(plugins.is_empty() && errors.is_empty());
+
+        // Each broken library is reported, without stopping at the first
+        let broken = ["a", "b"].map(|name| {
+            let path = dir.join(format!("{}.{}", name, std::env::consts::DLL_EXTENSION));
+            fs::write(&path, "not a library either").unwrap();
+            path
+        });
+        let (plugins, errors) = load_plugins(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(plugins.is_empty());
+        let paths = errors.into_iter().map(|e| e.path).collect::<Vec<_>>();
+        assert_eq!(paths, broken);
+    }
+}
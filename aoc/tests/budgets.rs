@@ -0,0 +1,60 @@
+//! Checks every day parses and solves each part within its budget, allowing
+//! for a debug build. Days run against their input where `aoc.toml` finds one
+//! or it was embedded, and otherwise against the examples their fuzz target
+//! is seeded with, which at least catches a solver that hangs.
+
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use util::{Config, Settings, CONFIG_FILE};
+
+#[test]
+fn days_are_within_budget() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let config_path = root.join(CONFIG_FILE);
+    let config =
+        Config::load_from(Some(&config_path), root, |_| None, &Settings::default()).unwrap();
+
+    let mut over = vec![];
+    for day in aoc::DAYS {
+        let path = config.input_path(day.year, day.day);
+        let inputs = match (day.embedded_input(), fs::read_to_string(&path)) {
+            (Some(input), _) => vec![String::from(input)],
+            (None, Ok(input)) => vec![input],
+            (None, Err(_)) => {
+                let target = config.day_crate(day.year, day.day).unwrap() + "_parse";
+                let seeds = root.join("fuzz/seeds").join(target);
+                let seeds = fs::read_dir(&seeds)
+                    .unwrap_or_else(|e| panic!("day {} has no input or examples: {}", day.day, e));
+                // The corpus also keeps inputs the parser has to reject, which
+                // have no answers to time
+                seeds
+                    .map(|seed| fs::read_to_string(seed.unwrap().path()).unwrap())
+                    .filter(|seed| day.parse(day.lines(seed)).is_ok())
+                    .collect()
+            }
+        };
+
+        for input in inputs {
+            let start = Instant::now();
+            let model = day.parse(day.lines(&input)).unwrap();
+            let parse_time = start.elapsed();
+            for part in [1, 2] {
+                let start = Instant::now();
+                day.solve_model(part, &*model);
+                let elapsed = parse_time + start.elapsed();
+                if elapsed > day.build_budget() {
+                    over.push(format!(
+                        "day {} part {}: {:.2?}, {:.2?} allowed",
+                        day.day,
+                        part,
+                        elapsed,
+                        day.build_budget()
+                    ));
+                }
+            }
+        }
+    }
+    assert!(over.is_empty(), "over budget:\n{}", over.join("\n"));
+}
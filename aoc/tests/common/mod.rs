@@ -0,0 +1,33 @@
+//! Builds the plugin fixtures in `tests/plugin` for the tests loading them.
+
+use std::env;
+use std::env::consts::{DLL_EXTENSION, DLL_PREFIX};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Builds the fixtures, returning a directory holding only the library of the
+/// one named `name`.
+pub fn build_fixture(name: &str) -> PathBuf {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = manifest_dir.join("../target/plugin-fixture");
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let output = Command::new(cargo)
+        .args(["build", "--quiet", "--workspace", "--manifest-path"])
+        .arg(manifest_dir.join("tests/plugin/Cargo.toml"))
+        // A separate target dir so the build doesn't wait on the running one
+        .env("CARGO_TARGET_DIR", &target_dir)
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "building the plugins failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let library = format!("{}aoc_plugin_{}.{}", DLL_PREFIX, name, DLL_EXTENSION);
+    let dir = env::temp_dir().join(format!("aoc-plugin-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy(target_dir.join("debug").join(&library), dir.join(&library)).unwrap();
+    dir
+}
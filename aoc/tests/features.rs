@@ -0,0 +1,75 @@
+//! Checks the crate builds with any selection of days. Registry entries only
+//! depend on their own day's feature, so building each day alone, none and all
+//! of them covers every combination, with and without `embed-input`.
+//!
+//! Each selection is a separate `cargo check`, which takes minutes, so these
+//! only run when asked for with `cargo test --test features -- --ignored`.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// The `y{year}-day{day}` features `all-days` enables.
+fn day_features() -> Vec<String> {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let manifest = fs::read_to_string(manifest).unwrap();
+    let start = manifest.find("all-days = [").expect("no all-days feature");
+    let list = &manifest[start..];
+    list[..list.find(']').unwrap()]
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .map(String::from)
+        .collect()
+}
+
+/// Runs `cargo check` on the crate's targets with only `features` enabled.
+fn check(features: &str) {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo"));
+    let output = Command::new(cargo)
+        .args([
+            "check",
+            "--quiet",
+            "--lib",
+            "--bins",
+            "--no-default-features",
+        ])
+        .args(["--features", features])
+        // A separate target dir so the check doesn't wait on the running build
+        .env("CARGO_TARGET_DIR", manifest_dir.join("../target/features"))
+        .env("RUSTFLAGS", "-D warnings")
+        .current_dir(manifest_dir)
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "build with features {:?} failed:\n{}",
+        features,
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+#[ignore = "slow: runs cargo check for every day"]
+fn every_day_builds_alone() {
+    check("");
+    let days = day_features();
+    assert!(days.len() > 1, "{:?}", days);
+    for day in days {
+        check(&day);
+    }
+    check("all-days");
+}
+
+/// Days embed their input where one exists and read it at runtime otherwise,
+/// whichever days are built.
+#[test]
+#[ignore = "slow: runs cargo check for each selection of days"]
+fn embeds_inputs() {
+    check("embed-input");
+    check("y2022-day6,embed-input");
+    check("y2015-day1,embed-input");
+    check("all-days,embed-input");
+}
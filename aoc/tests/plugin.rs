@@ -0,0 +1,65 @@
+//! Builds the plugin in `tests/plugin` and runs it through the runner: loaded,
+//! parsed, solved and freed.
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::Command;
+
+use libloading::Library;
+use util::Answer;
+
+mod common;
+
+#[test]
+fn runs_plugin() {
+    let dir = common::build_fixture("fixture");
+    let (plugins, errors) = aoc::load_plugins(&dir);
+    assert!(errors.is_empty(), "{:?}", errors);
+    let [plugin] = plugins[..] else {
+        panic!("expected one plugin, loaded {}", plugins.len());
+    };
+    assert_eq!(plugin.name, "fixture");
+    // Shares the plugin's statics, as the library is only loaded once
+    let library = unsafe { Library::new(&plugin.path) }.unwrap();
+    let live_models = unsafe {
+        library
+            .get::<unsafe extern "C" fn() -> usize>(b"fixture_live_models")
+            .unwrap()
+    };
+
+    let day = plugin.day();
+    assert_eq!((day.year, day.day), (2022, 25));
+    let model = day.parse(day.lines("1\n-4\n10\n")).unwrap();
+    assert_eq!(unsafe { live_models() }, 1);
+    assert_eq!(day.solve_model(1, &*model), Answer::from(7));
+    assert_eq!(day.solve_model(2, &*model), Answer::from(10));
+    drop(model);
+    assert_eq!(unsafe { live_models() }, 0);
+
+    let error = day.parse(day.lines("1\nx\n")).err().unwrap();
+    assert_eq!(error.to_string(), "line 2: \"x\" is not a number");
+    // A panic in the plugin comes back across the ABI as one in the runner
+    let model = day.parse(day.lines("")).unwrap();
+    panic::set_hook(Box::new(|_| {}));
+    let panicked = panic::catch_unwind(AssertUnwindSafe(|| day.solve_model(2, &*model)));
+    let _ = panic::take_hook();
+    let message = *panicked.unwrap_err().downcast::<String>().unwrap();
+    assert_eq!(message, "panicked: no numbers");
+    drop(model);
+    assert_eq!(unsafe { live_models() }, 0);
+
+    // And from the command line, as the day has no built-in solution
+    let input = dir.join("input.txt");
+    fs::write(&input, "2\n3\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .arg("--plugin-dir")
+        .arg(&dir)
+        .args(["--year", "2022", "run", "--day", "25", "--input"])
+        .arg(&input)
+        .output()
+        .unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("plugin fixture"), "{}", stdout);
+    assert!(stdout.contains("part 1: 5"), "{}", stdout);
+}
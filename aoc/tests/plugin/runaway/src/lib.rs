@@ -0,0 +1,40 @@
+//! A plugin solving day 24 of 2022 with parts that never finish, for the
+//! sandbox's tests: part 1 loops forever and part 2 allocates until it runs
+//! out of memory.
+
+use std::hint;
+
+use aoc_macros::aoc;
+use serde::Serialize;
+use util::{Normalize, ParseError};
+
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+/// How many lines the input has, as the parts ignore it.
+#[derive(Serialize)]
+pub struct Lines(usize);
+
+pub fn parse<T>(lines: T) -> Result<Lines, ParseError>
+where
+    T: Iterator<Item = String>,
+{
+    Ok(Lines(lines.count()))
+}
+
+#[aoc(day = 24, part = 1)]
+pub fn spin(lines: &Lines) -> usize {
+    let mut turns = lines.0;
+    loop {
+        turns = hint::black_box(turns.wrapping_add(1));
+    }
+}
+
+#[aoc(day = 24, part = 2)]
+pub fn hoard(lines: &Lines) -> usize {
+    let mut chunks = vec![];
+    loop {
+        chunks.push(hint::black_box(vec![lines.0 as u8; 64 << 20]));
+    }
+}
+
+util::export_plugin!("runaway", 2022, PART1, PART2);
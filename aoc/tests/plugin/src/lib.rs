@@ -0,0 +1,56 @@
+//! A plugin solving day 25 of 2022 with sums of numbers, for the runner's
+//! tests. It counts its live models, so they can check each one is freed.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use aoc_macros::aoc;
+use serde::Serialize;
+use util::{Normalize, ParseError};
+
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+static LIVE_MODELS: AtomicUsize = AtomicUsize::new(0);
+
+/// One number per line.
+#[derive(Serialize)]
+pub struct Numbers(Vec<i64>);
+
+impl Drop for Numbers {
+    fn drop(&mut self) {
+        LIVE_MODELS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn parse<T>(lines: T) -> Result<Numbers, ParseError>
+where
+    T: Iterator<Item = String>,
+{
+    let numbers = lines
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .map_err(|_| ParseError::at_line(i + 1, format!("{:?} is not a number", line)))
+        })
+        .collect::<Result<_, _>>()?;
+    LIVE_MODELS.fetch_add(1, Ordering::SeqCst);
+    Ok(Numbers(numbers))
+}
+
+#[aoc(day = 25, part = 1)]
+pub fn sum(numbers: &Numbers) -> i64 {
+    numbers.0.iter().sum()
+}
+
+/// Panics without any numbers, to check the panic is reported across the ABI.
+#[aoc(day = 25, part = 2)]
+pub fn largest(numbers: &Numbers) -> i64 {
+    *numbers.0.iter().max().expect("no numbers")
+}
+
+util::export_plugin!("fixture", 2022, PART1, PART2);
+
+/// Models parsed and not yet freed.
+#[no_mangle]
+pub extern "C" fn fixture_live_models() -> usize {
+    LIVE_MODELS.load(Ordering::SeqCst)
+}
@@ -0,0 +1,58 @@
+//! Runs a plugin day that never finishes in the sandbox, which must stop it
+//! and report why rather than hang or go down with it.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+/// Runs the runaway day sandboxed with the extra `args`, returning whether the
+/// run passed and what it printed.
+fn run_sandboxed(dir: &Path, args: &[&str]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .arg("--plugin-dir")
+        .arg(dir)
+        .args([
+            "--year",
+            "2022",
+            "run",
+            "--day",
+            "24",
+            "--sandbox",
+            "--input",
+        ])
+        .arg(dir.join("input.txt"))
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    (output.status.success(), stdout)
+}
+
+#[test]
+fn stops_runaway_days() {
+    let dir = common::build_fixture("runaway");
+    fs::write(dir.join("input.txt"), "1\n").unwrap();
+
+    let (passed, looped) = run_sandboxed(&dir, &["--part", "1", "--timeout", "1"]);
+    assert!(!passed, "{}", looped);
+    assert!(
+        looped.contains("2022 day 24: TIMED OUT after 1.00s"),
+        "{}",
+        looped
+    );
+
+    // The memory limit is only enforced on Unix
+    if cfg!(unix) {
+        let args = ["--part", "2", "--timeout", "30", "--memory-limit", "512"];
+        let (passed, hoarded) = run_sandboxed(&dir, &args);
+        assert!(!passed, "{}", hoarded);
+        assert!(
+            hoarded.contains("2022 day 24: OUT OF MEMORY (limit 512 MiB)"),
+            "{}",
+            hoarded
+        );
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,113 @@
+//! Runs `aoc serve` and sends it requests over HTTP.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+use serde_json::{json, Value};
+
+/// A running `aoc serve`, killed when dropped.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    fn start(args: &[&str]) -> Server {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_aoc"))
+            .args(["serve", "--addr", "127.0.0.1:0"])
+            .args(args)
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to run aoc serve");
+        // The first line has the port it was given
+        let mut line = String::new();
+        let mut stderr = BufReader::new(child.stderr.take().unwrap());
+        stderr.read_line(&mut line).unwrap();
+        let addr = line
+            .trim()
+            .strip_prefix("Listening on http://")
+            .unwrap_or_else(|| panic!("unexpected output {:?}", line))
+            .to_string();
+        // Keep draining its log so it never blocks on a full pipe
+        std::thread::spawn(move || std::io::copy(&mut stderr, &mut std::io::sink()));
+        Server { child, addr }
+    }
+
+    /// The status and JSON body of the response to `method url` with `body`.
+    fn request(&self, method: &str, url: &str, body: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            method,
+            url,
+            self.addr,
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+#[cfg(feature = "y2022-day6")]
+fn routes() {
+    let server = Server::start(&[]);
+    let (status, days) = server.request("GET", "/", "");
+    assert_eq!(status, 200);
+    assert!(days["days"]
+        .as_array()
+        .unwrap()
+        .contains(&json!({ "year": 2022, "day": 6 })));
+
+    let (status, solved) = server.request("POST", "/2022/6/1", "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n");
+    assert_eq!(status, 200, "{}", solved);
+    assert_eq!(solved["answer"], json!({ "type": "integer", "value": 7 }));
+    assert_eq!(solved["part"], 1);
+
+    assert_eq!(server.request("POST", "/2022/6/3", "").0, 404);
+    assert_eq!(server.request("POST", "/2022/26/1", "").0, 404);
+    assert_eq!(server.request("GET", "/2022/6/1", "").0, 405);
+    assert_eq!(server.request("GET", "/days", "").0, 404);
+    // Too short to have a marker, which parse checks for
+    assert_eq!(server.request("POST", "/2022/6/1", "abc\n").0, 422);
+    // Only has the marker part 1 needs, so part 2 panics in its child
+    let (status, failed) = server.request("POST", "/2022/6/2", "abcd\n");
+    assert_eq!(status, 500, "{}", failed);
+    assert!(failed["error"].as_str().unwrap().contains("panicked"));
+}
+
+#[test]
+#[cfg(feature = "y2022-day6")]
+fn limits_body() {
+    let server = Server::start(&["--max-body", "16"]);
+    let (status, failed) = server.request("POST", "/2022/6/1", &"a".repeat(17));
+    assert_eq!(status, 413);
+    assert_eq!(failed["error"], "input is over the 16 byte limit");
+    assert_eq!(server.request("POST", "/2022/6/1", "abcdabcd\n").0, 200);
+}
+
+#[test]
+#[cfg(feature = "y2022-day6")]
+fn times_out() {
+    // No child starts in no time, so every request is killed
+    let server = Server::start(&["--timeout", "0"]);
+    let (status, failed) = server.request("POST", "/2022/6/1", "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n");
+    assert_eq!(status, 504);
+    assert_eq!(failed["error"], "no answer within 0ns");
+    // The server itself carries on
+    assert_eq!(server.request("GET", "/", "").0, 200);
+}
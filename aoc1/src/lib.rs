@@ -0,0 +1,150 @@
+use std::iter::Sum;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
+
+use aoc_macros::aoc;
+use util::{Normalize, ParseError};
+
+/// Calorie lists are trimmed line by line anyway, so any normalization is safe.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+mod elf_heap {
+    use super::*;
+
+    pub struct ElfHeap<T: Ord> {
+        min_heap: BinaryHeap<Reverse<T>>,
+        desired_size: usize,
+    }
+
+    impl<T> ElfHeap<T> where T: Ord + Sum {
+        pub fn with_size(size: usize) -> Self {
+            ElfHeap { min_heap: BinaryHeap::<Reverse<T>>::new(), desired_size: size }
+        }
+
+        pub fn push(&mut self, elt: T) {
+            self.min_heap.push(Reverse(elt));
+            while self.min_heap.len() > self.desired_size {
+                self.min_heap.pop();
+            }
+        }
+
+        fn drain_sorted(self) -> Vec<T> {
+            self.min_heap.into_sorted_vec().into_iter().map(|i| i.0).collect()
+        }
+
+        pub fn sum(self) -> T {
+            self.drain_sorted().into_iter().sum()
+        }
+    }
+}
+
+use elf_heap::ElfHeap;
+
+/// Calories of each item carried, grouped by elf.
+pub fn parse<T>(lines: T) -> Result<Vec<Vec<usize>>, ParseError>
+where
+    T: Iterator<Item = String>,
+{
+    let mut elves = vec![vec![]];
+    for (i, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            elves.push(vec![]);
+        } else {
+            let calories = line.trim().parse::<usize>().map_err(|e| {
+                ParseError::at_line(i + 1, format!("could not parse {:?} as calories: {}", line, e))
+            })?;
+            elves.last_mut().unwrap().push(calories);
+        }
+    }
+    Ok(elves)
+}
+
+/// Sum of the calorie totals of the `num_elves` elves carrying the most calories.
+pub fn top_calories(elves: &[Vec<usize>], num_elves: usize) -> usize {
+    // Track elves with `num_elves` highest calorie totals
+    let mut elf_heap = ElfHeap::with_size(num_elves);
+    for elf in elves {
+        elf_heap.push(elf.iter().sum());
+    }
+
+    elf_heap.sum()
+}
+
+/// Calories carried by the elf carrying the most.
+#[aoc(day = 1, part = 1)]
+pub fn most_calories(elves: &[Vec<usize>]) -> usize {
+    top_calories(elves, 1)
+}
+
+/// Calories carried by the elf carrying the most, kept as a running maximum
+/// rather than in a heap.
+#[aoc(day = 1, part = 1, variant = "scan")]
+pub fn most_calories_scan(elves: &[Vec<usize>]) -> usize {
+    let mut most = 0;
+    for elf in elves {
+        let calories = elf.iter().sum();
+        if calories > most {
+            most = calories;
+        }
+    }
+    most
+}
+
+/// Calories carried by the three elves carrying the most.
+#[aoc(day = 1, part = 2)]
+pub fn top_three_calories(elves: &[Vec<usize>]) -> usize {
+    top_calories(elves, 3)
+}
+
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    /// Total up every elf, sort descending and sum the first `num_elves`.
+    pub fn top_calories<T>(lines: T, num_elves: usize) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        let mut totals = vec![0];
+        for line in lines {
+            if line.trim().is_empty() {
+                totals.push(0);
+            } else {
+                *totals.last_mut().unwrap() += line.trim().parse::<usize>().unwrap();
+            }
+        }
+        totals.sort_unstable_by(|a, b| b.cmp(a));
+        totals.into_iter().take(num_elves).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn render(elves: &[Vec<usize>]) -> Vec<String> {
+        let mut lines = vec![];
+        for elf in elves {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            lines.extend(elf.iter().map(|c| c.to_string()));
+        }
+        lines
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(
+            elves in prop::collection::vec(prop::collection::vec(1..100_000usize, 1..6), 1..30),
+            num_elves in 1..5usize,
+        ) {
+            let lines = render(&elves);
+            let elves = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(most_calories_scan(&elves), most_calories(&elves));
+            prop_assert_eq!(
+                top_calories(&elves, num_elves),
+                reference::top_calories(lines.into_iter(), num_elves)
+            );
+        }
+    }
+}
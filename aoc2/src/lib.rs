@@ -0,0 +1,270 @@
+mod rps {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    pub enum RPS {
+        Rock,
+        Paper,
+        Scissors,
+    }
+    
+    impl From<&str> for RPS {
+        fn from(c: &str) -> Self {
+            match c {
+                "A" | "X" => RPS::Rock,
+                "B" | "Y" => RPS::Paper,
+                "C" | "Z" => RPS::Scissors,
+                _ => panic!("str {} does not match a valid rock-paper-scissors option", c),
+            }
+        }
+    }
+
+    impl From<&RPS> for i32 {
+        fn from(opt: &RPS) -> i32 {
+            match opt {
+                RPS::Rock => 0,
+                RPS::Paper => 1,
+                RPS::Scissors => 2,
+            }
+        }
+    }
+
+    impl From<i32> for RPS {
+        fn from(val: i32) -> RPS {
+            match val {
+                0 => RPS::Rock,
+                1 => RPS::Paper,
+                2 => RPS::Scissors,
+                _ => panic!("i32 {} does not match a valid rock-paper-scissors option", val),
+            }
+        }
+    }
+
+    impl RPS {
+        pub fn determine_response(&self, result_str: &str) -> Self {
+            let diff = match result_str {
+                "X" => -1,
+                "Y" => 0,
+                "Z" => 1,
+                _ => panic!("str {} does not match a valid win/loss/draw option", result_str),
+            };
+            let response = (<&RPS as Into<i32>>::into(self) + diff).rem_euclid(3);
+            response.into()
+        }
+
+        fn score_by_type(&self) -> usize {
+            match self {
+                RPS::Rock => 1,
+                RPS::Paper => 2,
+                RPS::Scissors => 3,
+            }
+        }
+
+        pub fn score(&self, other: &RPS) -> usize {
+            let diff = (<&RPS as Into<i32>>::into(self) - <&RPS as Into<i32>>::into(other)).rem_euclid(3);
+            let score_against = match diff {
+                0 => 3,
+                1 => 6,
+                2 => 0,
+                _ => panic!("{diff}"),
+            };
+
+            score_against + self.score_by_type()
+        }
+    }
+}
+
+/// The `rps` module part 1 was first solved with, reading both columns of
+/// the strategy guide as hands, before part 2 gave the second one a new meaning.
+mod rps_by_hand {
+    #[derive(Debug)]
+    pub enum RPS {
+        Rock,
+        Paper,
+        Scissors,
+    }
+    
+    impl From<&str> for RPS {
+        fn from(c: &str) -> Self {
+            match c {
+                "A" | "X" => RPS::Rock,
+                "B" | "Y" => RPS::Paper,
+                "C" | "Z" => RPS::Scissors,
+                _ => panic!("str {} does not match a valid rock-paper-scissors option", c),
+            }
+        }
+    }
+
+    impl From<&RPS> for i32 {
+        fn from(opt: &RPS) -> i32 {
+            match opt {
+                RPS::Rock => 0,
+                RPS::Paper => 1,
+                RPS::Scissors => 2,
+            }
+        }
+    }
+
+    impl RPS {
+        fn score_by_type(&self) -> usize {
+            match self {
+                RPS::Rock => 1,
+                RPS::Paper => 2,
+                RPS::Scissors => 3,
+            }
+        }
+
+        pub fn score(&self, other: &RPS) -> usize {
+            let diff = (<&RPS as Into<i32>>::into(self) - <&RPS as Into<i32>>::into(other)).rem_euclid(3);
+            let score_against = match diff {
+                0 => 3,
+                1 => 6,
+                2 => 0,
+                _ => panic!("{diff}"),
+            };
+
+            score_against + self.score_by_type()
+        }
+    }
+}
+
+pub use rps::RPS;
+use aoc_macros::aoc;
+use util::{Normalize, ParseError};
+
+/// Rounds are split on whitespace, so any normalization is safe.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+/// Opponent's hand and the strategy guide's second column for each round.
+pub fn parse<T>(lines: T) -> Result<Vec<(RPS, String)>, ParseError>
+where
+    T: Iterator<Item = String>,
+{
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let c: Vec<&str> = line.split_whitespace().collect();
+            match c.as_slice() {
+                [elf @ ("A" | "B" | "C"), response @ ("X" | "Y" | "Z")] => {
+                    Ok((RPS::from(*elf), String::from(*response)))
+                }
+                _ => Err(ParseError::at_line(
+                    i + 1,
+                    format!("expected a hand A-C and a response X-Z, found {:?}", line),
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Total score when reading the strategy guide's second column as the hand to play (part 1).
+#[aoc(day = 2, part = 1)]
+pub fn total_score_by_hand(rounds: &[(RPS, String)]) -> usize {
+    let mut score = 0;
+    for (elf_hand, response) in rounds {
+        let my_hand = RPS::from(response.as_str());
+        score += my_hand.score(elf_hand);
+    }
+    score
+}
+
+/// Total score by hand as first written, with both columns read as hands by
+/// the original `rps` module.
+#[aoc(day = 2, part = 1, variant = "by-hand")]
+pub fn total_score_by_hand_original(rounds: &[(RPS, String)]) -> usize {
+    let mut score = 0;
+    for (elf_hand, response) in rounds {
+        // Back to the column the elf's hand was read from
+        let elf_column = ["A", "B", "C"][i32::from(elf_hand) as usize];
+        let hands: Vec<rps_by_hand::RPS> = [elf_column, response.as_str()]
+            .into_iter()
+            .map(rps_by_hand::RPS::from)
+            .collect();
+        score += hands[1].score(&hands[0]);
+    }
+    score
+}
+
+/// Total score when reading the strategy guide's second column as the outcome (part 2).
+#[aoc(day = 2, part = 2)]
+pub fn total_score_by_outcome(rounds: &[(RPS, String)]) -> usize {
+    let mut score = 0;
+    for (elf_hand, response) in rounds {
+        let my_hand = elf_hand.determine_response(response);
+        score += my_hand.score(elf_hand);
+    }
+    score
+}
+
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    /// Looks every round up in the full table of opponent hand and my hand.
+    pub fn total_score_by_hand<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        lines
+            .map(|line| match line.trim() {
+                "A X" => 4, // rock draws with rock
+                "A Y" => 8, // paper beats rock
+                "A Z" => 3, // scissors lose to rock
+                "B X" => 1, // rock loses to paper
+                "B Y" => 5, // paper draws with paper
+                "B Z" => 9, // scissors beat paper
+                "C X" => 7, // rock beats scissors
+                "C Y" => 2, // paper loses to scissors
+                "C Z" => 6, // scissors draw with scissors
+                other => panic!("unexpected round {other}"),
+            })
+            .sum()
+    }
+
+    /// Looks every round up in the full table of opponent hand and desired outcome.
+    pub fn total_score_by_outcome<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        lines
+            .map(|line| match line.trim() {
+                "A X" => 3, // rock, lose with scissors
+                "A Y" => 4, // rock, draw with rock
+                "A Z" => 8, // rock, win with paper
+                "B X" => 1, // paper, lose with rock
+                "B Y" => 5, // paper, draw with paper
+                "B Z" => 9, // paper, win with scissors
+                "C X" => 2, // scissors, lose with paper
+                "C Y" => 6, // scissors, draw with scissors
+                "C Z" => 7, // scissors, win with rock
+                other => panic!("unexpected round {other}"),
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn matches_reference(
+            rounds in prop::collection::vec(("[ABC]", "[XYZ]"), 0..100),
+        ) {
+            let lines = rounds
+                .into_iter()
+                .map(|(elf, outcome)| format!("{elf} {outcome}"))
+                .collect::<Vec<_>>();
+            let rounds = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(total_score_by_hand_original(&rounds), total_score_by_hand(&rounds));
+            prop_assert_eq!(
+                total_score_by_hand(&rounds),
+                reference::total_score_by_hand(lines.clone().into_iter())
+            );
+            prop_assert_eq!(
+                total_score_by_outcome(&rounds),
+                reference::total_score_by_outcome(lines.into_iter())
+            );
+        }
+    }
+}
@@ -1,26 +1,50 @@
 #![feature(iter_array_chunks)]
-use std::{io::{Lines, BufReader}, fs::File};
-
-pub fn run(lines: Lines<BufReader<File>>) {
-    // // Part One
-    // let mut sum = 0;
-    // for line in lines {
-    //     let line = line.expect("Could not parse line");
-    //     sum += rucksack::get_line_priority(line);
-    // }
-    // println!("Total priority is: {}", sum);
-
-    // Part Two
-    let mut sum = 0;
-    for group in lines.array_chunks::<3>() {
-        let group = group.map(|l| l.unwrap());
-        sum += rucksack::get_group_priority(group);
-    }
-    println!("Total priority is: {}", sum);
+use aoc_macros::aoc;
+use util::{Normalize, ParseError};
+
+/// Trailing whitespace would be counted as rucksack items.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+/// Rucksack contents, one line per rucksack.
+pub fn parse<T>(lines: T) -> Result<Vec<String>, ParseError>
+where
+    T: Iterator<Item = String>,
+{
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            if !line.chars().all(|c| c.is_ascii_alphabetic()) {
+                Err(ParseError::at_line(i + 1, format!("items must be letters a-z or A-Z, found {:?}", line)))
+            } else if line.len() % 2 != 0 {
+                Err(ParseError::at_line(i + 1, "compartments must hold the same number of items"))
+            } else {
+                Ok(line)
+            }
+        })
+        .collect()
+}
+
+/// Sum of the priorities of the item shared by both compartments of each rucksack.
+#[aoc(day = 3, part = 1)]
+pub fn line_priority_sum(rucksacks: &[String]) -> u64 {
+    rucksacks
+        .iter()
+        .map(|line| rucksack::get_line_priority(line))
+        .sum()
+}
+
+/// Sum of the priorities of the badge shared by each group of three rucksacks.
+#[aoc(day = 3, part = 2)]
+pub fn group_priority_sum(rucksacks: &[String]) -> u64 {
+    rucksacks
+        .iter()
+        .array_chunks::<3>()
+        .map(rucksack::get_group_priority)
+        .sum()
 }
 
 mod rucksack {
-    pub fn get_line_priority(line: String) -> u64 {
+    pub fn get_line_priority(line: &str) -> u64 {
         let mut bitstring: u64 = 0; // acts as bit string
         let threshold = line.len() / 2;
         for (i, c) in line.char_indices() {
@@ -35,7 +59,7 @@ mod rucksack {
         panic!("Should have found matching char by end of loop");
     }
 
-    pub fn get_group_priority(group: [String; 3]) -> u64 {
+    pub fn get_group_priority(group: [&String; 3]) -> u64 {
         let mut bitstring = u64::MAX;
         for line in group {
             let mut this_bitstring = 0;
@@ -63,14 +87,92 @@ mod rucksack {
     }
 }
 
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    fn priority(c: char) -> u64 {
+        ('a'..='z').chain('A'..='Z').position(|x| x == c).unwrap() as u64 + 1
+    }
+
+    /// Searches the second compartment for each item of the first.
+    pub fn line_priority_sum<T>(lines: T) -> u64
+    where
+        T: Iterator<Item = String>,
+    {
+        lines
+            .map(|line| {
+                let (first, second) = line.split_at(line.len() / 2);
+                priority(first.chars().find(|c| second.contains(*c)).unwrap())
+            })
+            .sum()
+    }
+
+    /// Searches the other two rucksacks of a group for each item of the first.
+    pub fn group_priority_sum<T>(lines: T) -> u64
+    where
+        T: Iterator<Item = String>,
+    {
+        let lines = lines.collect::<Vec<_>>();
+        lines
+            .chunks(3)
+            .map(|group| {
+                let badge = group[0]
+                    .chars()
+                    .find(|c| group[1].contains(*c) && group[2].contains(*c))
+                    .unwrap();
+                priority(badge)
+            })
+            .sum()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::rucksack::{get_line_priority, char_to_ind};
+    use crate::{group_priority_sum, line_priority_sum, parse, reference};
+    use proptest::prelude::*;
+
+    const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+    /// Items sharing exactly `shared` across `num_parts` parts: every other
+    /// item type is assigned to a single part by `owners`.
+    fn build_parts(shared: usize, owners: &[usize], picks: &[usize], num_parts: usize) -> Vec<String> {
+        let items = ITEMS.chars().collect::<Vec<_>>();
+        let mut parts = vec![vec![items[shared]]; num_parts];
+        for (part, item) in picks.iter().enumerate().map(|(i, pick)| (i % num_parts, pick % items.len())) {
+            if item != shared && owners[item] % num_parts == part {
+                parts[part].push(items[item]);
+            }
+        }
+        parts.into_iter().map(|p| p.into_iter().collect()).collect()
+    }
+
+    fn rucksack() -> impl Strategy<Value = String> {
+        (0..52usize, prop::collection::vec(any::<usize>(), 52), prop::collection::vec(any::<usize>(), 0..60))
+            .prop_map(|(shared, owners, picks)| {
+                let mut halves = build_parts(shared, &owners, &picks, 2);
+                // Both compartments hold the same number of items
+                let len = halves[0].len().min(halves[1].len());
+                halves.iter_mut().for_each(|h| h.truncate(len));
+                halves.concat()
+            })
+    }
+
+    fn group() -> impl Strategy<Value = Vec<String>> {
+        (0..52usize, prop::collection::vec(any::<usize>(), 52), prop::collection::vec(any::<usize>(), 0..60))
+            .prop_map(|(shared, owners, picks)| {
+                // Every rucksack has an even number of items, so repeat the badge where needed
+                let mut group = build_parts(shared, &owners, &picks, 3);
+                for rucksack in group.iter_mut().filter(|r| r.len() % 2 != 0) {
+                    rucksack.push(rucksack.chars().next().unwrap());
+                }
+                group
+            })
+    }
 
     #[test]
     #[should_panic]
     fn no_repeat() {
-        get_line_priority(String::from("abcd"));
+        get_line_priority("abcd");
     }
 
     #[test]
@@ -80,4 +182,23 @@ mod tests {
         assert_eq!(char_to_ind('A'), 27);
         assert_eq!(char_to_ind('Z'), 52);
     }
+
+    proptest! {
+        #[test]
+        fn line_priority_matches_reference(lines in prop::collection::vec(rucksack(), 0..20)) {
+            prop_assert_eq!(
+                line_priority_sum(&parse(lines.clone().into_iter()).unwrap()),
+                reference::line_priority_sum(lines.into_iter())
+            );
+        }
+
+        #[test]
+        fn group_priority_matches_reference(groups in prop::collection::vec(group(), 0..10)) {
+            let lines = groups.concat();
+            prop_assert_eq!(
+                group_priority_sum(&parse(lines.clone().into_iter()).unwrap()),
+                reference::group_priority_sum(lines.into_iter())
+            );
+        }
+    }
 }
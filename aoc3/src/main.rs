@@ -1,6 +1,3 @@
-use util;
-
 fn main() {
-    let lines = util::read_lines(util::input_file_path()).expect("Could not parse input file lines");
-    aoc3::run(lines);
+    aoc3::PART2.main();
 }
@@ -1,7 +1,14 @@
+use aoc_macros::aoc;
+use serde::{Deserialize, Serialize};
+use util::{Normalize, ParseError};
+
+/// Trailing whitespace would fail the range pair parser.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
 mod parser {
     use crate::ElfRange;
     use nom::{
-        bytes::complete::tag, character::complete::digit1, combinator::map_res,
+        bytes::complete::tag, character::complete::digit1, combinator::{all_consuming, map_res},
         sequence::separated_pair, Parser,
     };
     use std::str::FromStr;
@@ -16,63 +23,68 @@ mod parser {
 
     pub fn parser<'a>() -> impl Parser<&'a str, (ElfRange, ElfRange), ()> {
         move |input: &'a str| {
-            let (_, (range1, range2)) =
-                separated_pair(range_pair_parser(), tag(","), range_pair_parser())(input)?;
-            let r1 = ElfRange::from(range1);
-            let r2 = ElfRange::from(range2);
+            let (_, (r1, r2)) = all_consuming(separated_pair(
+                map_res(range_pair_parser(), ElfRange::try_from),
+                tag(","),
+                map_res(range_pair_parser(), ElfRange::try_from),
+            ))(input)?;
 
             Ok(("", (r1, r2)))
         }
     }
 }
 
-pub fn run1<T>(lines: T)
+/// The pair of section ranges assigned on each line.
+pub fn parse<T>(lines: T) -> Result<Vec<(ElfRange, ElfRange)>, ParseError>
 where
     T: Iterator<Item = String>,
 {
     use nom::Parser;
 
-    println!(
-        "{}",
-        lines
-            .filter(|line| {
-                let (_, (range1, range2)) = parser::parser()
-                    .parse(line.as_str())
-                    .expect("Failed to parse");
-                range1.is_subset(&range2) || range2.is_subset(&range1)
-            })
-            .count()
-    )
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let (_, pair) = parser::parser().parse(line.as_str()).map_err(|_| {
+                ParseError::at_line(i + 1, format!("expected a pair of ranges like 2-4,6-8, found {:?}", line))
+            })?;
+            Ok(pair)
+        })
+        .collect()
 }
 
-pub fn run2<T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    use nom::Parser;
+/// Number of pairs where one range fully contains the other.
+#[aoc(day = 4, part = 1)]
+pub fn count_contained(pairs: &[(ElfRange, ElfRange)]) -> usize {
+    pairs
+        .iter()
+        .filter(|(range1, range2)| range1.is_subset(range2) || range2.is_subset(range1))
+        .count()
+}
 
-    println!(
-        "{}",
-        lines
-            .filter(|line| {
-                let (_, (range1, range2)) = parser::parser()
-                    .parse(line.as_str())
-                    .expect("Failed to parse");
-                range1.overlaps(&range2)
-            })
-            .count()
-    )
+/// Number of pairs whose ranges overlap at all.
+#[aoc(day = 4, part = 2)]
+pub fn count_overlapping(pairs: &[(ElfRange, ElfRange)]) -> usize {
+    pairs
+        .iter()
+        .filter(|(range1, range2)| range1.overlaps(range2))
+        .count()
 }
 
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ElfRange {
     start: usize,
     end: usize,
 }
 
-impl From<(usize, usize)> for ElfRange {
-    fn from((start, end): (usize, usize)) -> Self {
-        assert!(start <= end);
-        ElfRange { start, end }
+impl TryFrom<(usize, usize)> for ElfRange {
+    type Error = ();
+
+    fn try_from((start, end): (usize, usize)) -> Result<Self, Self::Error> {
+        if start <= end {
+            Ok(ElfRange { start, end })
+        } else {
+            Err(())
+        }
     }
 }
 
@@ -94,16 +106,83 @@ impl ElfRange {
     }
 }
 
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    use std::collections::HashSet;
+
+    fn sections(line: &str) -> (HashSet<usize>, HashSet<usize>) {
+        let to_set = |range: &str| {
+            let (start, end) = range.split_once('-').unwrap();
+            (start.parse::<usize>().unwrap()..=end.parse().unwrap()).collect::<HashSet<_>>()
+        };
+        let (first, second) = line.split_once(',').unwrap();
+        (to_set(first), to_set(second))
+    }
+
+    /// Compares the full sets of sections assigned to each elf.
+    pub fn count_contained<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        lines
+            .map(|line| sections(&line))
+            .filter(|(first, second)| first.is_subset(second) || second.is_subset(first))
+            .count()
+    }
+
+    /// Checks the sets of sections assigned to each elf for a common section.
+    pub fn count_overlapping<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        lines
+            .map(|line| sections(&line))
+            .filter(|(first, second)| !first.is_disjoint(second))
+            .count()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use nom::Parser;
+    use proptest::prelude::*;
 
     use super::*;
 
+    fn range() -> impl Strategy<Value = String> {
+        (1..30usize, 0..10usize).prop_map(|(start, len)| format!("{}-{}", start, start + len))
+    }
+
     #[test]
     fn parse_subset() {
         let s = "2-3,2-69";
         let (_, (r1, r2)) = parser::parser().parse(s).unwrap();
         assert!(r1.is_subset(&r2));
     }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        for line in ["3-2,4-5", "2-3,4-5x", "2-3", "99999999999999999999-1,1-2"] {
+            assert!(parse([String::from(line)].into_iter()).is_err(), "{line}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(pairs in prop::collection::vec((range(), range()), 0..50)) {
+            let lines = pairs
+                .into_iter()
+                .map(|(first, second)| format!("{first},{second}"))
+                .collect::<Vec<_>>();
+            let pairs = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(
+                count_contained(&pairs),
+                reference::count_contained(lines.clone().into_iter())
+            );
+            prop_assert_eq!(
+                count_overlapping(&pairs),
+                reference::count_overlapping(lines.into_iter())
+            );
+        }
+    }
 }
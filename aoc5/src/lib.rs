@@ -1,6 +1,11 @@
-use std::default::Default;
+use aoc_macros::aoc;
+use serde::{Deserialize, Serialize};
+use util::{Normalize, ParseError};
 
-#[derive(Debug, PartialEq, Eq)]
+/// The crate drawing is fixed-width, so trailing whitespace is left alone.
+pub const NORMALIZE: Normalize = Normalize::STANDARD;
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CrateMove {
     num_crates: usize,
     from: usize,
@@ -9,19 +14,11 @@ pub struct CrateMove {
 
 type CrateStack = Vec<char>;
 
-#[derive(Debug)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CargoShip {
     stacks: Vec<CrateStack>,
 }
 
-impl Default for CargoShip {
-    fn default() -> Self {
-        Self {
-            stacks: vec![vec![]; 9],
-        }
-    }
-}
-
 impl CargoShip {
     fn move_crates_9000(&mut self, m: &CrateMove) {
         let split_ind = self.stacks[m.from].len() - m.num_crates;
@@ -30,6 +27,14 @@ impl CargoShip {
         self.stacks[m.to].append(&mut to_move);
     }
 
+    /// Moves crates one at a time, as the CrateMover 9000 does.
+    fn move_crates_one_at_a_time(&mut self, m: &CrateMove) {
+        for _ in 0..m.num_crates {
+            let c = self.stacks[m.from].pop().expect("moved more crates than the stack holds");
+            self.stacks[m.to].push(c);
+        }
+    }
+
     // Repeated code but I'm so sick of this problem at this point I don't care to fix it
     fn move_crates_9001(&mut self, m: &CrateMove) {
         let split_ind = self.stacks[m.from].len() - m.num_crates;
@@ -40,14 +45,22 @@ impl CargoShip {
     fn get_final_orientation(&self) -> String {
         self.stacks
             .iter()
-            .map(|stack| stack[stack.len() - 1])
+            .filter_map(|stack| stack.last())
             .collect::<String>()
     }
 
     fn push_to_stack(&mut self, stack_ind: usize, c: char) {
+        self.ensure_stacks(stack_ind + 1);
         self.stacks[stack_ind].push(c);
     }
 
+    /// Adds empty stacks until there are at least `num_stacks`.
+    fn ensure_stacks(&mut self, num_stacks: usize) {
+        if self.stacks.len() < num_stacks {
+            self.stacks.resize(num_stacks, vec![]);
+        }
+    }
+
     fn flipped(mut self) -> Self {
         for stack in self.stacks.iter_mut() {
             stack.reverse();
@@ -60,11 +73,12 @@ mod parser {
     use std::num::NonZeroUsize;
 
     use super::{CargoShip, CrateMove};
+    use util::ParseError;
     use nom::{
         branch::alt,
         bytes::complete::{tag, take},
         character::complete::{digit1, satisfy},
-        combinator::map_res,
+        combinator::{all_consuming, map_res},
         sequence::{delimited, pair, tuple},
         Parser,
     };
@@ -134,7 +148,7 @@ mod parser {
         }
     }
 
-    fn ship_parser<T>() -> impl Parser<T, CargoShip, ()>
+    fn ship_parser<T>() -> impl Parser<T, CargoShip, ParseError>
     where
         T: Iterator<Item = String>,
     {
@@ -148,23 +162,36 @@ mod parser {
                 let mut crates_parsed = 0;
                 while !line_ref.is_empty() {
                     // Parse a crate and add to stack
-                    if let Ok((_line_ref, c)) = crate_parser().parse(line_ref) {
-                        // Save remainder into line_ref
-                        line_ref = _line_ref;
-                        // Push char to the proper CrateStack
-                        if let Some(c) = c {
-                            crates_parsed += 1;
-                            ship.push_to_stack(stack, c);
-                        }
-
-                        stack += 1;
+                    let (_line_ref, c) = crate_parser().parse(line_ref).map_err(|_| {
+                        nom::Err::Failure(ParseError::new(format!(
+                            "could not parse crates from {:?}",
+                            line
+                        )))
+                    })?;
+                    // Save remainder into line_ref
+                    line_ref = _line_ref;
+                    // Push char to the proper CrateStack
+                    if let Some(c) = c {
+                        crates_parsed += 1;
+                        ship.push_to_stack(stack, c);
                     }
+
+                    stack += 1;
                 }
 
                 // We will hit this on the line that lists indices
                 if crates_parsed == 0 {
-                    iter.next().unwrap(); // skip the newline
-                    break;
+                    ship.ensure_stacks(line.split_whitespace().count());
+                    // skip the newline
+                    match iter.next() {
+                        Some(blank) if !blank.trim().is_empty() => {
+                            return Err(nom::Err::Failure(ParseError::new(format!(
+                                "expected a blank line after the stack indices, found {:?}",
+                                blank
+                            ))))
+                        }
+                        _ => break,
+                    }
                 }
             }
 
@@ -186,7 +213,7 @@ mod parser {
                 map_res(digit1, NonZeroUsize::from_str),
             ));
 
-            let (_, (_, num_crates, _, from, _, to)) = tuple_parser.parse(input)?;
+            let (tail, (_, num_crates, _, from, _, to)) = tuple_parser.parse(input)?;
 
             let num_crates = usize::from(num_crates);
             // Go from 1-indexed to 0-indexed
@@ -194,7 +221,7 @@ mod parser {
             let to = usize::from(to) - 1;
 
             Ok((
-                "",
+                tail,
                 CrateMove {
                     num_crates,
                     from,
@@ -204,58 +231,297 @@ mod parser {
         }
     }
 
-    fn moves_parser<T>() -> impl Parser<T, Vec<CrateMove>, ()>
+    fn moves_parser<T>() -> impl Parser<T, Vec<CrateMove>, ParseError>
     where
         T: Iterator<Item = String>,
     {
         move |mut iter: T| {
             let mut moves = vec![];
             while let Some(line) = iter.next() {
-                moves.push((move_parser().parse(line.as_str()).unwrap()).1)
+                let (_, m) = all_consuming(move_parser())
+                    .parse(line.as_str())
+                    .map_err(|_| {
+                        nom::Err::Failure(ParseError::new(format!(
+                            "could not parse move from {:?}",
+                            line
+                        )))
+                    })?;
+                moves.push(m);
             }
             Ok((iter, moves))
         }
     }
 
-    pub fn parser<T>() -> impl Parser<T, (CargoShip, Vec<CrateMove>), ()>
+    pub fn parser<T>() -> impl Parser<T, (CargoShip, Vec<CrateMove>), ParseError>
     where
         T: Iterator<Item = String>,
     {
         move |iter: T| {
             // Parse ship
+            let span = util::span("drawing parse");
             let (iter, ship) = ship_parser().parse(iter)?;
+            drop(span);
             // Parse moves
+            let span = util::span("move parse");
             let (iter, moves) = moves_parser().parse(iter)?;
+            drop(span);
+
+            // Every move has to be between stacks that exist, and take no more
+            // crates than its stack holds by then
+            let num_stacks = ship.stacks.len();
+            let mut heights = ship.stacks.iter().map(Vec::len).collect::<Vec<_>>();
+            for m in &moves {
+                if m.from >= num_stacks || m.to >= num_stacks {
+                    return Err(nom::Err::Failure(ParseError::new(format!(
+                        "{:?} refers to a stack beyond the {} on the ship",
+                        m, num_stacks
+                    ))));
+                }
+                if m.num_crates > heights[m.from] {
+                    return Err(nom::Err::Failure(ParseError::new(format!(
+                        "{:?} takes more crates than the {} on the stack",
+                        m, heights[m.from]
+                    ))));
+                }
+                heights[m.from] -= m.num_crates;
+                heights[m.to] += m.num_crates;
+            }
 
             Ok((iter, (ship, moves)))
         }
     }
 }
 
-pub fn run1<T>(lines: T)
+/// The starting stacks of crates and the moves to apply to them.
+pub fn parse<T>(lines: T) -> Result<(CargoShip, Vec<CrateMove>), ParseError>
 where
     T: Iterator<Item = String>,
 {
     use nom::Parser;
-    let (_, (mut cargo_ship, crate_moves)) =
-        parser::parser().parse(lines).expect("Failed to parse");
+
+    match parser::parser().parse(lines) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(nom::Err::Error(e) | nom::Err::Failure(e)) => Err(e),
+        Err(nom::Err::Incomplete(_)) => Err(ParseError::new("incomplete input")),
+    }
+}
+
+/// Top crate of each stack after the CrateMover 9000 has moved crates one at a time.
+#[aoc(day = 5, part = 1)]
+pub fn top_crates_9000((ship, crate_moves): &(CargoShip, Vec<CrateMove>)) -> String {
+    let _span = util::span("simulation");
+    let mut cargo_ship = ship.clone();
 
     for m in crate_moves {
-        cargo_ship.move_crates_9000(&m);
+        cargo_ship.move_crates_9000(m);
     }
-    print!("{}", cargo_ship.get_final_orientation());
+    cargo_ship.get_final_orientation()
 }
 
-pub fn run2<T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    use nom::Parser;
-    let (_, (mut cargo_ship, crate_moves)) =
-        parser::parser().parse(lines).expect("Failed to parse");
+/// Top crate of each stack after the CrateMover 9000, moving each crate on its own
+/// rather than reversing the moved crates as a block.
+#[aoc(day = 5, part = 1, variant = "one-at-a-time")]
+pub fn top_crates_9000_one_at_a_time((ship, crate_moves): &(CargoShip, Vec<CrateMove>)) -> String {
+    let _span = util::span("simulation");
+    let mut cargo_ship = ship.clone();
 
     for m in crate_moves {
-        cargo_ship.move_crates_9001(&m);
+        cargo_ship.move_crates_one_at_a_time(m);
+    }
+    cargo_ship.get_final_orientation()
+}
+
+/// Top crate of each stack after the CrateMover 9001 has moved crates together.
+#[aoc(day = 5, part = 2)]
+pub fn top_crates_9001((ship, crate_moves): &(CargoShip, Vec<CrateMove>)) -> String {
+    let _span = util::span("simulation");
+    let mut cargo_ship = ship.clone();
+
+    for m in crate_moves {
+        cargo_ship.move_crates_9001(m);
+    }
+    cargo_ship.get_final_orientation()
+}
+
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    /// Crate count, source and destination stack (0-indexed) of a move.
+    type Move = (usize, usize, usize);
+
+    /// Reads crates by column position and the moves by splitting on whitespace.
+    fn parse<T>(lines: T) -> (Vec<Vec<char>>, Vec<Move>)
+    where
+        T: Iterator<Item = String>,
+    {
+        let mut lines = lines;
+        let drawing = lines.by_ref().take_while(|l| !l.trim().is_empty()).collect::<Vec<_>>();
+        let (indices, crates) = drawing.split_last().unwrap();
+        let mut stacks = vec![vec![]; indices.split_whitespace().count()];
+        for row in crates.iter().rev() {
+            for (i, stack) in stacks.iter_mut().enumerate() {
+                if let Some(c) = row.chars().nth(1 + 4 * i).filter(|c| c.is_ascii_uppercase()) {
+                    stack.push(c);
+                }
+            }
+        }
+        let moves = lines
+            .map(|l| {
+                let nums = l
+                    .split_whitespace()
+                    .filter_map(|w| w.parse::<usize>().ok())
+                    .collect::<Vec<_>>();
+                (nums[0], nums[1] - 1, nums[2] - 1)
+            })
+            .collect();
+        (stacks, moves)
+    }
+
+    fn tops(stacks: &[Vec<char>]) -> String {
+        stacks.iter().filter_map(|s| s.last()).collect()
+    }
+
+    /// Moves one crate at a time.
+    pub fn top_crates_9000<T>(lines: T) -> String
+    where
+        T: Iterator<Item = String>,
+    {
+        let (mut stacks, moves) = parse(lines);
+        for (num, from, to) in moves {
+            for _ in 0..num {
+                let c = stacks[from].pop().unwrap();
+                stacks[to].push(c);
+            }
+        }
+        tops(&stacks)
+    }
+
+    /// Moves crates one at a time through a holding stack, preserving their order.
+    pub fn top_crates_9001<T>(lines: T) -> String
+    where
+        T: Iterator<Item = String>,
+    {
+        let (mut stacks, moves) = parse(lines);
+        for (num, from, to) in moves {
+            let mut held = vec![];
+            for _ in 0..num {
+                held.push(stacks[from].pop().unwrap());
+            }
+            while let Some(c) = held.pop() {
+                stacks[to].push(c);
+            }
+        }
+        tops(&stacks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// Renders stacks (bottom first) and moves in the puzzle's input format.
+    fn render(stacks: &[Vec<char>], moves: &[(usize, usize, usize)]) -> Vec<String> {
+        let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+        let mut lines = vec![];
+        for level in (0..height).rev() {
+            let row = stacks
+                .iter()
+                .map(|s| s.get(level).map_or(String::from("   "), |c| format!("[{c}]")))
+                .collect::<Vec<_>>();
+            lines.push(row.join(" "));
+        }
+        lines.push(
+            (1..=stacks.len())
+                .map(|i| format!(" {i} "))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        lines.push(String::new());
+        for (num, from, to) in moves {
+            lines.push(format!("move {} from {} to {}", num, from + 1, to + 1));
+        }
+        lines
+    }
+
+    /// A ship with `1..=9` stacks and a sequence of moves that is valid for it.
+    fn ship() -> impl Strategy<Value = Vec<String>> {
+        let stacks = prop::collection::vec(prop::collection::vec(prop::char::range('A', 'Z'), 0..8), 1..=9);
+        let moves = prop::collection::vec((any::<usize>(), any::<usize>(), any::<usize>()), 0..30);
+        (stacks, moves).prop_map(|(stacks, raw_moves)| {
+            // Track stack heights so every move takes only crates that exist
+            let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+            let mut moves = vec![];
+            for (num, from, to) in raw_moves {
+                let (from, to) = (from % stacks.len(), to % stacks.len());
+                if from == to || heights[from] == 0 {
+                    continue;
+                }
+                let num = 1 + num % heights[from];
+                heights[from] -= num;
+                heights[to] += num;
+                moves.push((num, from, to));
+            }
+            render(&stacks, &moves)
+        })
+    }
+
+    fn lines(input: &str) -> impl Iterator<Item = String> + '_ {
+        input.lines().map(String::from)
+    }
+
+    #[test]
+    fn example() {
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2";
+        let parsed = parse(lines(input)).unwrap();
+        assert_eq!(top_crates_9000(&parsed), "CMZ");
+        assert_eq!(top_crates_9001(&parsed), "MCD");
+    }
+
+    #[test]
+    fn example_normalized() {
+        let input = "\u{feff}    [D]    \r\n[N] [C]    \r\n[Z] [M] [P]\r\n 1   2   3 \r\n\r\nmove 1 from 2 to 1\r\nmove 3 from 1 to 3\r\nmove 2 from 2 to 1\r\nmove 1 from 1 to 2\r\n\r\n";
+        let input = NORMALIZE.apply(input);
+        let parsed = parse(lines(&input)).unwrap();
+        assert_eq!(top_crates_9000(&parsed), "CMZ");
+    }
+
+    #[test]
+    fn example_ship() {
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1";
+        let (ship, _) = parse(lines(input)).unwrap();
+        util::assert_debug_snapshot!("example_ship", ship);
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        // Used to loop forever, as the crate parser never advanced past "[A"
+        assert!(parse(lines("[A\n 1 \n\nmove 1 from 1 to 1")).is_err());
+        assert!(parse(lines("[A]\n 1 \nmove 1 from 1 to 1")).is_err());
+        assert!(parse(lines("[A]\n 1 \n\nmove 1 from 1 to 2")).is_err());
+        assert!(parse(lines("[A]\n 1 \n\nmove 1 from 1 to 1 please")).is_err());
+        // Used to underflow working out where to split the stack
+        assert!(parse(lines("[A]    \n 1   2 \n\nmove 2 from 1 to 2")).is_err());
+        assert!(parse(lines("[A]    \n 1   2 \n\nmove 1 from 1 to 2\nmove 1 from 1 to 2")).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference_9000(lines in ship()) {
+            let parsed = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(top_crates_9000_one_at_a_time(&parsed), top_crates_9000(&parsed));
+            prop_assert_eq!(
+                top_crates_9000(&parsed),
+                reference::top_crates_9000(lines.into_iter())
+            );
+        }
+
+        #[test]
+        fn matches_reference_9001(lines in ship()) {
+            let parsed = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(
+                top_crates_9001(&parsed),
+                reference::top_crates_9001(lines.into_iter())
+            );
+        }
     }
-    print!("{}", cargo_ship.get_final_orientation());
 }
@@ -1,46 +1,130 @@
 use std::collections::HashMap;
 
-fn get_start_by_distinct(line: String, num_distinct: usize) -> usize {
+use aoc_macros::aoc;
+use util::{Normalize, ParseError};
+
+/// A trailing blank line would otherwise be read as a second datastream.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+fn get_start_by_distinct(line: &str, num_distinct: usize) -> Option<usize> {
     let mut char_map = HashMap::<char, usize>::new();
     let chars = line.chars().collect::<Vec<char>>();
     for (i, c) in chars.iter().enumerate() {
-        // Add one to char_map[c]
-        char_map.entry(*c).and_modify(|x| *x += 1).or_insert(1);
-
-        // Check for duplicates
-        if i >= num_distinct - 1 && char_map.values().into_iter().all(|count| *count == 1) {
-            return i;
-        }
-
         // If we're far enough, delete the character that has expired
         // from consideration for the distinct sequence
         if i >= num_distinct {
-            let four_back = chars[i - num_distinct];
-            char_map.entry(four_back).and_modify(|count| *count -= 1);
-            if *char_map.get(&four_back).unwrap() == 0 {
-                char_map.remove(&four_back);
+            let expired = chars[i - num_distinct];
+            char_map.entry(expired).and_modify(|count| *count -= 1);
+            if *char_map.get(&expired).unwrap() == 0 {
+                char_map.remove(&expired);
             }
         }
+
+        // Add one to char_map[c]
+        char_map.entry(*c).and_modify(|x| *x += 1).or_insert(1);
+
+        // Check for duplicates, answering with the number of characters processed
+        if i >= num_distinct - 1 && char_map.values().all(|count| *count == 1) {
+            return Some(i + 1);
+        }
     }
-    panic!()
+    None
 }
 
-pub fn run1<T>(mut lines: T)
+/// The datastream buffer, which must be a single line with a start-of-packet
+/// marker. It may lack a start-of-message marker, which only part 2 needs.
+pub fn parse<T>(mut lines: T) -> Result<String, ParseError>
 where
     T: Iterator<Item = String>,
 {
-    let line = lines.next().unwrap();
-    assert!(lines.next().is_none());
+    let line = lines.next().ok_or_else(|| ParseError::new("input is empty"))?;
+    if lines.next().is_some() {
+        return Err(ParseError::at_line(2, "datastream must be a single line"));
+    }
+    if get_start_by_distinct(&line, 4).is_none() {
+        return Err(ParseError::at_line(1, "no start-of-packet marker, as no 4 characters in a row differ"));
+    }
+    Ok(line)
+}
 
-    println!("{}", get_start_by_distinct(line, 4));
+/// Number of characters processed before the first `num_distinct` distinct characters.
+pub fn first_marker(signal: &str, num_distinct: usize) -> usize {
+    get_start_by_distinct(signal, num_distinct)
+        .unwrap_or_else(|| panic!("no marker, as no {} characters in a row differ", num_distinct))
 }
 
-pub fn run2<T>(mut lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    let line = lines.next().unwrap();
-    assert!(lines.next().is_none());
+/// Characters processed before the first start-of-packet marker.
+#[aoc(day = 6, part = 1)]
+pub fn start_of_packet(signal: &str) -> usize {
+    first_marker(signal, 4)
+}
+
+/// Characters processed before the first start-of-message marker.
+#[aoc(day = 6, part = 2)]
+pub fn start_of_message(signal: &str) -> usize {
+    first_marker(signal, 14)
+}
+
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    use std::collections::HashSet;
+
+    /// Checks every window of `num_distinct` characters for a repeat.
+    pub fn first_marker<T>(mut lines: T, num_distinct: usize) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        let chars = lines.next().unwrap().chars().collect::<Vec<_>>();
+        chars
+            .windows(num_distinct)
+            .position(|w| w.iter().collect::<HashSet<_>>().len() == num_distinct)
+            .unwrap()
+            + num_distinct
+    }
+}
 
-    println!("{}", get_start_by_distinct(line, 14));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn examples() {
+        let examples = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (line, packet, message) in examples {
+            assert_eq!(first_marker(line, 4), packet);
+            assert_eq!(first_marker(line, 14), message);
+        }
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        let parse_line = |line: &str| parse(std::iter::once(String::from(line)));
+        assert!(parse_line("abcd").is_ok());
+        // Used to be accepted, and then panic solving either part
+        assert!(parse_line("aaaa").is_err());
+        assert!(parse_line("abc").is_err());
+        assert!(parse_line("").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(
+            // Small alphabet so repeats are common, followed by a guaranteed marker
+            prefix in "[a-e]{0,40}",
+            num_distinct in prop::sample::select(vec![4usize, 14]),
+        ) {
+            let line = format!("{prefix}abcdefghijklmn");
+            prop_assert_eq!(
+                first_marker(&line, num_distinct),
+                reference::first_marker([line].into_iter(), num_distinct)
+            );
+        }
+    }
 }
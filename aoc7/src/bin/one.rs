@@ -1,9 +1,3 @@
-use aoc7::run1;
-use util;
-
 fn main() {
-    let lines = util::read_lines(util::input_file_path())
-        .expect("Could not parse input file lines")
-        .map(|l| l.expect("Could not parse line"));
-    run1(lines);
+    aoc7::PART1.main();
 }
@@ -1,14 +1,21 @@
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
 
-trait FsEntrySize<'a> {
-    fn get_size(&self) -> usize;
-}
+use aoc_macros::aoc;
+use serde::{Deserialize, Serialize};
+use util::Normalize;
+
+/// Trailing whitespace would otherwise end up in file names.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+const SMALL_DIR_THRESHOLD: usize = 100_000;
+const TOTAL_DISK: usize = 70_000_000;
+const SPACE_NEEDED: usize = 30_000_000;
 
 /// Represents a dir.
-/// The lifetime generic 'a specifies that the dyn FsEntrySize trait objects
-/// must live for lifetime of this object (helps with add_child).
+/// The lifetime generic 'a ties child dirs to the lifetime of this object
+/// (helps with add_child).
 #[derive(Default)] // root dir
 pub struct Dir<'a> {
     parent: Option<Rc<RefCell<Dir<'a>>>>,
@@ -43,16 +50,16 @@ impl<'a> Dir<'a> {
 }
 
 // ----- Functions on Rc<RefCell<Dir>> -----
-fn apply_op(dir: Rc<RefCell<Dir>>, op: FsOperation) -> Rc<RefCell<Dir>> {
+fn apply_op<'a>(dir: Rc<RefCell<Dir<'a>>>, op: &FsOperation) -> Rc<RefCell<Dir<'a>>> {
     match op {
-        FsOperation::cd(to) => cd(dir, to).expect("failed to apply cd command op"),
+        FsOperation::cd(to) => cd(dir, to.clone()).expect("parse checks each cd has somewhere to go"),
         FsOperation::ls(entries) => {
             for entry in entries {
                 match entry {
                     FsEntry::Dir(dir_name) => dir
                         .borrow_mut()
                         .add_dir(Dir::with_name(dir_name, dir.clone())),
-                    FsEntry::File(file) => dir.borrow_mut().add_file(file),
+                    FsEntry::File(file) => dir.borrow_mut().add_file(file.clone()),
                 }
             }
             dir
@@ -82,30 +89,14 @@ fn get_root(mut dir: Rc<RefCell<Dir>>) -> Rc<RefCell<Dir>> {
 }
 // -----------------------------------------
 
-impl<'a> FsEntrySize<'a> for Dir<'a> {
-    fn get_size(&self) -> usize {
-        self.child_dirs
-            .values()
-            .map(|child| child.borrow_mut().get_size())
-            .sum::<usize>()
-            + self.files.values().sum::<usize>()
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct File {
     name: String,
     size: usize,
 }
 
-impl<'a> FsEntrySize<'a> for File {
-    fn get_size(&self) -> usize {
-        self.size
-    }
-}
-
 #[allow(non_camel_case_types)]
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum FsOperation {
     // TODO find a way to make this &str ?
     cd(String),
@@ -114,16 +105,19 @@ pub enum FsOperation {
 
 /// Represents a filesystem entry to be inserted.
 /// The parser returns a vec of these within `FsOperation::ls`.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum FsEntry {
     File(File),
     Dir(String),
 }
 
 mod parser {
+    use std::collections::HashSet;
+
     use crate::File;
 
-    use super::{FsEntry, FsOperation};
+    use super::{FsEntry, FsOperation, Session};
+    use util::ParseError;
     use nom::{
         branch::alt,
         bytes::complete::tag,
@@ -133,13 +127,15 @@ mod parser {
         Parser,
     };
 
-    impl From<(&str, &str)> for FsEntry {
-        fn from((size_str, file_name): (&str, &str)) -> Self {
+    impl TryFrom<(&str, &str)> for FsEntry {
+        type Error = std::num::ParseIntError;
+
+        fn try_from((size_str, file_name): (&str, &str)) -> Result<Self, Self::Error> {
             use std::str::FromStr;
-            FsEntry::File(File {
+            Ok(FsEntry::File(File {
                 name: String::from(file_name),
-                size: usize::from_str(size_str).expect("failed to parse file size string to file"),
-            })
+                size: usize::from_str(size_str)?,
+            }))
         }
     }
 
@@ -154,7 +150,7 @@ mod parser {
             // Parser consumes the whole line to create an FsEntry
             let (tail, insert) = all_consuming::<_, _, (), _>(alt((
                 into::<_, _, FsEntry, (), (), _>(preceded(tag("dir "), alphanumeric1)),
-                into::<_, _, FsEntry, (), (), _>(separated_pair(digit1, tag(" "), rest)),
+                map_res(separated_pair(digit1, tag(" "), rest), FsEntry::try_from),
             )))
             .parse(line)
             .map_err(|_| nom::Err::Error(()))?;
@@ -182,32 +178,52 @@ mod parser {
         }
     }
 
-    pub fn parse<T>(lines: T) -> Vec<FsOperation>
+    /// The commands run in the terminal, with the output of each `ls`. Every
+    /// `cd` has to go to the root, up from below it or into a dir an `ls` has
+    /// listed, so the solvers can always follow it.
+    pub fn parse<T>(lines: T) -> Result<Session, ParseError>
     where
         T: Iterator<Item = String>,
     {
-        let mut lines = lines.peekable();
+        let _span = util::span("transcript parse");
+        let mut lines = lines.enumerate().peekable();
 
+        // Path of the current dir from the root, and of every dir listed so far
+        let mut cwd = vec![];
+        let mut listed = HashSet::new();
         let mut ops = vec![];
-        while let Some(line) = lines.next() {
+        while let Some((i, line)) = lines.next() {
             let (_, op) = all_consuming(fs_oper_parser())
                 .parse(line.as_str())
-                .expect("could not parse FsOperation from line starting with $");
+                .map_err(|_| {
+                    ParseError::at_line(i + 1, format!("expected a command starting with $, found {:?}", line))
+                })?;
             let op = match op {
-                FsOperation::cd(to) => FsOperation::cd(to),
+                FsOperation::cd(to) => {
+                    match to.as_str() {
+                        "/" => cwd.clear(),
+                        ".." => {
+                            if cwd.pop().is_none() {
+                                return Err(ParseError::at_line(i + 1, "cd .. at the root"));
+                            }
+                        }
+                        dir => {
+                            cwd.push(String::from(dir));
+                            if !listed.contains(&cwd) {
+                                let message = format!("cd into {:?}, which no ls has listed", dir);
+                                return Err(ParseError::at_line(i + 1, message));
+                            }
+                        }
+                    }
+                    FsOperation::cd(to)
+                }
                 // Fills in ls's vec with result lines below
                 FsOperation::ls(_) => {
                     // Loop, peek next line and try to parse FsInsert
                     let mut fs_entries = vec![];
-                    loop {
-                        if let Some(line) = lines.peek() {
-                            if let Ok((_, entry)) =
-                                all_consuming(ls_result_parser()).parse(line.as_str())
-                            {
-                                fs_entries.push(entry);
-                            } else {
-                                break;
-                            }
+                    while let Some((_, line)) = lines.peek() {
+                        if let Ok((_, entry)) = all_consuming(ls_result_parser()).parse(line.as_str()) {
+                            fs_entries.push(entry);
                         } else {
                             break;
                         }
@@ -216,94 +232,286 @@ mod parser {
                     }
 
                     // We should've parsed at least one ls entry
-                    assert!(!fs_entries.is_empty());
+                    if fs_entries.is_empty() {
+                        return Err(ParseError::at_line(i + 1, "ls listed no entries"));
+                    }
+                    for entry in &fs_entries {
+                        if let FsEntry::Dir(name) = entry {
+                            let mut path = cwd.clone();
+                            path.push(name.clone());
+                            listed.insert(path);
+                        }
+                    }
                     FsOperation::ls(fs_entries)
                 }
             };
             ops.push(op);
         }
 
-        ops
+        Ok(Session::from(ops))
     }
 }
 
-pub fn run1<'r, T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    let ops = parser::parse(lines);
-
-    let mut dir = Rc::new(RefCell::new(Dir::default()));
-    for op in ops {
-        dir = apply_op(dir, op);
-    }
+pub use parser::parse;
 
-    // Go to root
-    let root = get_root(dir);
+/// The parsed terminal session, with the dir sizes shared by both parts.
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    ops: Vec<FsOperation>,
+    // Worked out by the solvers, not parsed
+    #[serde(skip)]
+    sizes: OnceCell<Vec<usize>>,
+}
 
-    let threshold = 100_000;
-    #[allow(unused_variables)]
-    let mut total_size = 0;
-    let mut dirs = vec![root];
-    while let Some(dir) = dirs.pop() {
-        // Add child dirs to search
-        let mut children = dir
-            .borrow_mut()
-            .child_dirs
-            .values()
-            .map(|x| x.clone())
-            .collect::<Vec<Rc<RefCell<Dir>>>>();
-        dirs.append(&mut children);
-
-        // Add to total size if under threshold
-        let size = dir.borrow_mut().get_size();
-        if size <= threshold {
-            total_size += size;
+impl From<Vec<FsOperation>> for Session {
+    fn from(ops: Vec<FsOperation>) -> Self {
+        Session {
+            ops,
+            sizes: OnceCell::new(),
         }
     }
+}
 
-    println!("Summed size of dirs with size less than {threshold} is {total_size}");
+impl Session {
+    /// Total size of every dir with the root last, computed on first use.
+    pub fn dir_sizes(&self) -> &[usize] {
+        self.sizes.get_or_init(|| {
+            let span = util::span("tree build");
+            let mut dir = Rc::new(RefCell::new(Dir::default()));
+            for op in &self.ops {
+                dir = apply_op(dir, op);
+            }
+            let root = get_root(dir);
+            drop(span);
+
+            let _span = util::span("size aggregation");
+            let mut sizes = vec![];
+            collect_sizes(&root.borrow(), &mut sizes);
+            sizes
+        })
+    }
 }
 
-pub fn run2<T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    let ops = parser::parse(lines);
+/// Pushes the size of every dir below and including `dir` in post-order,
+/// returning the size of `dir`.
+fn collect_sizes(dir: &Dir, sizes: &mut Vec<usize>) -> usize {
+    let size = dir
+        .child_dirs
+        .values()
+        .map(|child| collect_sizes(&child.borrow(), sizes))
+        .sum::<usize>()
+        + dir.files.values().sum::<usize>();
+    sizes.push(size);
+    size
+}
 
-    let mut dir = Rc::new(RefCell::new(Dir::default()));
-    for op in ops {
-        dir = apply_op(dir, op);
-    }
+/// Summed size of all dirs with a total size of at most 100000.
+#[aoc(day = 7, part = 1)]
+pub fn small_dirs_total(session: &Session) -> usize {
+    session
+        .dir_sizes()
+        .iter()
+        .filter(|size| **size <= SMALL_DIR_THRESHOLD)
+        .sum()
+}
 
-    // Go to root
-    let root = get_root(dir);
+/// Size of the smallest dir that frees up enough space for the update when deleted.
+#[aoc(day = 7, part = 2)]
+pub fn smallest_dir_to_delete(session: &Session) -> usize {
+    let sizes = session.dir_sizes();
+    let space_used = sizes.last().copied().unwrap_or_default();
 
-    let total_disk = 70_000_000;
-    let space_needed = 30_000_000;
-    let space_used = root.borrow().get_size();
-    let mut dirs = vec![root];
     let mut smallest_satisfactory = usize::MAX;
-    while let Some(dir) = dirs.pop() {
-        // Add child dirs to search
-        let mut children = dir
-            .borrow_mut()
-            .child_dirs
-            .values()
-            .map(|x| x.clone())
-            .collect::<Vec<Rc<RefCell<Dir>>>>();
-        dirs.append(&mut children);
-
+    for &this_size in sizes {
         // Set new smallest satisfactory if appropriate
-        let this_size = dir.borrow().get_size();
         if this_size < smallest_satisfactory
-            && (total_disk + this_size)
-                .checked_sub(space_used + space_needed)
+            && (TOTAL_DISK + this_size)
+                .checked_sub(space_used + SPACE_NEEDED)
                 .is_some()
         {
             smallest_satisfactory = this_size;
         }
     }
 
-    println!("Smallest dir to delete in order to satisfy {space_needed} space needed on a drive with capacity {total_disk} (current usage: {space_used}) is {smallest_satisfactory}");
+    smallest_satisfactory
+}
+
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    use super::{SMALL_DIR_THRESHOLD, SPACE_NEEDED, TOTAL_DISK};
+    use std::collections::HashMap;
+
+    /// Total size of every dir, keyed by its path from the root, found by adding
+    /// each file's size to every dir above it.
+    fn dir_sizes<T>(lines: T) -> HashMap<Vec<String>, usize>
+    where
+        T: Iterator<Item = String>,
+    {
+        let mut cwd = vec![];
+        let mut sizes = HashMap::from([(vec![], 0)]);
+        for line in lines {
+            match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["$", "cd", "/"] => cwd.clear(),
+                ["$", "cd", ".."] => {
+                    cwd.pop();
+                }
+                ["$", "cd", dir] => cwd.push(dir.to_string()),
+                ["$", "ls"] => {}
+                ["dir", dir] => {
+                    let mut path = cwd.clone();
+                    path.push(dir.to_string());
+                    sizes.entry(path).or_insert(0);
+                }
+                [size, _] => {
+                    let size = size.parse::<usize>().unwrap();
+                    for depth in 0..=cwd.len() {
+                        *sizes.entry(cwd[..depth].to_vec()).or_insert(0) += size;
+                    }
+                }
+                other => panic!("unexpected line {other:?}"),
+            }
+        }
+        sizes
+    }
+
+    pub fn small_dirs_total<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        dir_sizes(lines)
+            .into_values()
+            .filter(|size| *size <= SMALL_DIR_THRESHOLD)
+            .sum()
+    }
+
+    pub fn smallest_dir_to_delete<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        let sizes = dir_sizes(lines);
+        let to_free = (sizes[&vec![]] + SPACE_NEEDED).saturating_sub(TOTAL_DISK);
+        sizes.into_values().filter(|size| *size >= to_free).min().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[derive(Clone, Debug)]
+    struct Tree {
+        files: Vec<usize>,
+        dirs: Vec<Tree>,
+    }
+
+    fn file_size() -> impl Strategy<Value = usize> {
+        // Mostly small files so the threshold matters, with the odd large one
+        // so part 2 has to choose a dir other than the smallest
+        prop_oneof![4 => 1..50_000usize, 1 => 1..15_000_000usize]
+    }
+
+    /// A dir tree where every dir has at least one entry.
+    fn tree() -> impl Strategy<Value = Tree> {
+        let leaf = prop::collection::vec(file_size(), 1..4).prop_map(|files| Tree { files, dirs: vec![] });
+        leaf.prop_recursive(4, 32, 4, |inner| {
+            (prop::collection::vec(file_size(), 0..4), prop::collection::vec(inner, 1..4))
+                .prop_map(|(files, dirs)| Tree { files, dirs })
+        })
+    }
+
+    /// Renders the terminal output of a depth-first walk of `tree`.
+    fn render(tree: &Tree, lines: &mut Vec<String>) {
+        lines.push(String::from("$ ls"));
+        for i in 0..tree.dirs.len() {
+            lines.push(format!("dir d{i}"));
+        }
+        for (i, size) in tree.files.iter().enumerate() {
+            lines.push(format!("{size} f{i}.txt"));
+        }
+        for (i, dir) in tree.dirs.iter().enumerate() {
+            lines.push(format!("$ cd d{i}"));
+            render(dir, lines);
+            lines.push(String::from("$ cd .."));
+        }
+    }
+
+    fn transcript() -> impl Strategy<Value = Vec<String>> {
+        tree().prop_map(|tree| {
+            let mut lines = vec![String::from("$ cd /")];
+            render(&tree, &mut lines);
+            lines
+        })
+    }
+
+    /// Draws the dir tree the way the puzzle does, with entries sorted by name.
+    fn draw(dir: &Dir, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        let name = match depth {
+            0 => "/",
+            _ => dir.get_name(),
+        };
+        out.push_str(&format!("{}- {} (dir, size={})\n", indent, name, collect_sizes(dir, &mut vec![])));
+
+        let mut children = dir.child_dirs.values().cloned().collect::<Vec<_>>();
+        children.sort_by(|a, b| a.borrow().dir_name.cmp(&b.borrow().dir_name));
+        for child in children {
+            draw(&child.borrow(), depth + 1, out);
+        }
+        let mut files = dir.files.iter().collect::<Vec<_>>();
+        files.sort();
+        for (name, size) in files {
+            out.push_str(&format!("{}  - {} (file, size={})\n", indent, name, size));
+        }
+    }
+
+    #[test]
+    fn example_tree() {
+        let input = "$ cd /\n$ ls\ndir a\n14848514 b.txt\n8504156 c.dat\ndir d\n$ cd a\n$ ls\ndir e\n29116 f\n2557 g\n62596 h.lst\n$ cd e\n$ ls\n584 i\n$ cd ..\n$ cd ..\n$ cd d\n$ ls\n4060174 j\n8033020 d.log\n5626152 d.ext\n7214296 k";
+        let mut dir = Rc::new(RefCell::new(Dir::default()));
+        for op in &parse(input.lines().map(String::from)).unwrap().ops {
+            dir = apply_op(dir, op);
+        }
+
+        let mut tree = String::new();
+        draw(&get_root(dir).borrow(), 0, &mut tree);
+        util::assert_snapshot!("example_tree", tree);
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        let inputs = [
+            "$ cd /\n$ ls\n$ cd a",
+            "$ cd /\n$ ls\n123\n",
+            "$ rm -rf /",
+            "$ cd /\n$ ls\n99999999999999999999999 big.txt",
+            // cd into a dir no ls has listed, or up from the root, which the
+            // solvers could not follow
+            "$ cd x\n$ ls\n1 a.txt",
+            "$ cd /\n$ ls\ndir a\n$ cd b",
+            "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir b\n$ cd ..\n$ cd b",
+            "$ cd /\n$ cd ..",
+        ];
+        for input in inputs {
+            assert!(parse(input.lines().map(String::from)).is_err(), "{input}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn small_dirs_matches_reference(lines in transcript()) {
+            prop_assert_eq!(
+                small_dirs_total(&parse(lines.clone().into_iter()).unwrap()),
+                reference::small_dirs_total(lines.into_iter())
+            );
+        }
+
+        #[test]
+        fn dir_to_delete_matches_reference(lines in transcript()) {
+            prop_assert_eq!(
+                smallest_dir_to_delete(&parse(lines.clone().into_iter()).unwrap()),
+                reference::smallest_dir_to_delete(lines.into_iter())
+            );
+        }
+    }
 }
@@ -1,5 +1,12 @@
 use std::{cmp::max, collections::HashMap};
 
+use aoc_macros::aoc;
+use serde::{Deserialize, Serialize};
+use util::Normalize;
+
+/// The column count comes from the first line, so it must not carry a BOM or trailing whitespace.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
 mod parser {
     use crate::Grid;
     use nom::{
@@ -9,33 +16,44 @@ mod parser {
         Parser,
     };
     use std::str::FromStr;
+    use util::ParseError;
 
     fn row_parser<'a>() -> impl Parser<&'a str, Vec<usize>, ()> {
-        all_consuming(many1(map_res(take(1usize), |s| {
-            Ok::<usize, ()>(usize::from_str(s).expect("Could not parse string to usize"))
-        })))
+        all_consuming(many1(map_res(take(1usize), usize::from_str)))
     }
 
-    pub fn parse<T>(lines: T) -> Grid
+    /// The grid of tree heights, which must be rectangular.
+    pub fn parse<T>(lines: T) -> Result<Grid, ParseError>
     where
         T: Iterator<Item = String>,
     {
         let mut lines = lines.peekable();
-        let columns = lines.peek().unwrap().len();
+        let columns = lines
+            .peek()
+            .ok_or_else(|| ParseError::new("input is empty"))?
+            .len();
         let mut grid = vec![];
 
-        for line in lines {
-            let (_, mut row) = row_parser()
-                .parse(line.as_str())
-                .expect("Could not parse line str to Vec<usize>");
+        for (i, line) in lines.enumerate() {
+            let (_, mut row) = row_parser().parse(line.as_str()).map_err(|_| {
+                ParseError::at_line(i + 1, format!("expected a row of digits, found {:?}", line))
+            })?;
+            if row.len() != columns {
+                return Err(ParseError::at_line(
+                    i + 1,
+                    format!("expected {} trees in the row, found {}", columns, row.len()),
+                ));
+            }
 
             grid.append(&mut row);
         }
 
-        Grid::from((grid, columns))
+        Ok(Grid::from((grid, columns)))
     }
 }
 
+pub use parser::parse;
+
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
 enum Direction {
     Up,
@@ -55,10 +73,13 @@ impl Direction {
     }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GridPoint {
     val: usize,
-    // Highest trees seen in up, down, left, right
+    // Highest trees seen in up, down, left, right, worked out by the solvers
+    #[serde(skip)]
     highest: HashMap<Direction, usize>,
+    #[serde(skip)]
     visible: bool,
 }
 
@@ -72,6 +93,7 @@ pub struct GridPoint {
 //     }
 // }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Grid {
     columns: usize,
     grid: Vec<GridPoint>,
@@ -196,27 +218,157 @@ impl Grid {
     }
 }
 
-pub fn run1<T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    let mut grid = parser::parse(lines);
+/// Number of trees visible from outside the grid.
+#[aoc(day = 8, part = 1)]
+pub fn count_visible_trees(grid: &Grid) -> usize {
+    let mut grid = grid.clone();
     grid.fill_highest();
-    println!("number of visible trees is: {}", grid.count_visible());
+    grid.count_visible()
 }
 
-pub fn run2<T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    let grid = parser::parse(lines);
-    println!("highest scenic score is {}", grid.highest_scenic_score());
+/// Highest scenic score of any tree in the grid.
+#[aoc(day = 8, part = 2)]
+pub fn best_scenic_score(grid: &Grid) -> usize {
+    grid.highest_scenic_score()
+}
+
+/// Naive implementations used to cross-check the solutions.
+pub mod reference {
+    fn parse<T>(lines: T) -> Vec<Vec<u32>>
+    where
+        T: Iterator<Item = String>,
+    {
+        lines
+            .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
+            .collect()
+    }
+
+    /// Heights of the trees between (`row`, `col`) and each edge, nearest first.
+    fn sight_lines(grid: &[Vec<u32>], row: usize, col: usize) -> [Vec<u32>; 4] {
+        let column = grid.iter().map(|r| r[col]).collect::<Vec<_>>();
+        [
+            column[..row].iter().rev().copied().collect(),
+            column[row + 1..].to_vec(),
+            grid[row][..col].iter().rev().copied().collect(),
+            grid[row][col + 1..].to_vec(),
+        ]
+    }
+
+    /// Checks every tree against every other tree in its row and column.
+    pub fn count_visible_trees<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        let grid = parse(lines);
+        let mut visible = 0;
+        for row in 0..grid.len() {
+            for col in 0..grid[row].len() {
+                let height = grid[row][col];
+                if sight_lines(&grid, row, col)
+                    .iter()
+                    .any(|line| line.iter().all(|h| *h < height))
+                {
+                    visible += 1;
+                }
+            }
+        }
+        visible
+    }
+
+    /// Walks out from every tree in each direction.
+    pub fn best_scenic_score<T>(lines: T) -> usize
+    where
+        T: Iterator<Item = String>,
+    {
+        let grid = parse(lines);
+        let mut best = 0;
+        for row in 0..grid.len() {
+            for col in 0..grid[row].len() {
+                let height = grid[row][col];
+                let score = sight_lines(&grid, row, col)
+                    .iter()
+                    .map(|line| match line.iter().position(|h| *h >= height) {
+                        Some(blocked) => blocked + 1,
+                        None => line.len(),
+                    })
+                    .product();
+                best = best.max(score);
+            }
+        }
+        best
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    fn grid() -> impl Strategy<Value = Vec<String>> {
+        (1..8usize, 1..8usize).prop_flat_map(|(rows, columns)| {
+            prop::collection::vec(prop::collection::vec(0..6u32, columns), rows).prop_map(|grid| {
+                grid.into_iter()
+                    .map(|row| row.into_iter().map(|h| char::from_digit(h, 10).unwrap()).collect())
+                    .collect()
+            })
+        })
+    }
 
     #[test]
     fn basic() {}
+
+    #[test]
+    fn parse_normalized() {
+        let input = NORMALIZE.apply("\u{feff}30373\r\n25512 \r\n65332\r\n33549\r\n35390\r\n\r\n");
+        let grid = parse(input.lines().map(String::from)).unwrap();
+        assert_eq!(count_visible_trees(&grid), 21);
+    }
+
+    #[test]
+    fn example_visibility() {
+        let mut grid = parse("30373\n25512\n65332\n33549\n35390".lines().map(String::from)).unwrap();
+        grid.fill_highest();
+
+        // Heights of the visible trees, with hidden ones blanked out
+        let map = grid
+            .grid
+            .chunks(grid.columns)
+            .map(|row| {
+                row.iter()
+                    .map(|p| match p.visible {
+                        true => char::from_digit(p.val as u32, 10).unwrap(),
+                        false => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        util::assert_snapshot!("example_visibility", map.join("\n"));
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        for input in ["", "123\n45", "12a\n345", "\n"] {
+            assert!(parse(input.lines().map(String::from)).is_err(), "{input:?}");
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn visible_matches_reference(lines in grid()) {
+            let grid = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(
+                count_visible_trees(&grid),
+                reference::count_visible_trees(lines.into_iter())
+            );
+        }
+
+        #[test]
+        fn scenic_score_matches_reference(lines in grid()) {
+            let grid = parse(lines.clone().into_iter()).unwrap();
+            prop_assert_eq!(
+                best_scenic_score(&grid),
+                reference::best_scenic_score(lines.into_iter())
+            );
+        }
+    }
 }
@@ -0,0 +1,16 @@
+#!/bin/sh
+# Seeds each parse target's corpus with the example inputs and, when present,
+# the real puzzle input (which is not committed).
+set -e
+cd "$(dirname "$0")"
+for seeds in seeds/*_parse; do
+    target="${seeds#seeds/}"
+    # The target of crate `y2015_day01` is `y2015_day01_parse`, for the crate
+    # in `y2015/day01`; 2022's `aoc1` is in `aoc1`
+    dir="../$(echo "${target%_parse}" | tr _ /)"
+    mkdir -p "corpus/$target"
+    cp "$seeds/"* "corpus/$target/"
+    if [ -f "$dir/input.txt" ]; then
+        cp "$dir/input.txt" "corpus/$target/input"
+    fi
+done
@@ -1,3 +1,9 @@
+use aoc_macros::aoc;
+use util::{Normalize, ParseError};
+
+/// TODO check trailing whitespace is never significant in this format.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
 mod parser {
     use nom::Parser;
 
@@ -6,30 +12,32 @@ mod parser {
     }
 }
 
-pub fn run1<T>(lines: T)
+/// TODO describe the input.
+pub fn parse<T>(lines: T) -> Result<Vec<TODO>, ParseError>
 where
     T: Iterator<Item = String>,
 {
     use nom::Parser;
 
-    for line in lines {
-        let _todo = parser::parser()
-            .parse(line.as_str())
-            .expect("Failed to parse");
-    }
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            parser::parser()
+                .parse(line.as_str())
+                .map(|(_, todo)| todo)
+                .map_err(|_| ParseError::at_line(i + 1, format!("expected TODO, found {:?}", line)))
+        })
+        .collect()
 }
 
-pub fn run2<T>(lines: T)
-where
-    T: Iterator<Item = String>,
-{
-    use nom::Parser;
+#[aoc(day = TODO, part = 1)]
+pub fn part1(input: &[TODO]) -> usize {
+    todo!()
+}
 
-    for line in lines {
-        let _todo = parser::parser()
-            .parse(line.as_str())
-            .expect("Failed to parse");
-    }
+#[aoc(day = TODO, part = 2)]
+pub fn part2(input: &[TODO]) -> usize {
+    todo!()
 }
 
 #[cfg(test)]
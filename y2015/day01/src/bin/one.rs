@@ -0,0 +1,3 @@
+fn main() {
+    y2015_day01::PART1.main();
+}
@@ -0,0 +1,64 @@
+use aoc_macros::aoc;
+use util::{Normalize, ParseError};
+
+/// The instructions are one line, so surrounding whitespace never matters.
+pub const NORMALIZE: Normalize = Normalize::TRIMMED;
+
+/// Floors moved by each instruction, `(` going up one and `)` down one.
+pub fn parse<T>(lines: T) -> Result<Vec<i32>, ParseError>
+where
+    T: Iterator<Item = String>,
+{
+    let mut steps = vec![];
+    for (i, line) in lines.enumerate() {
+        for c in line.chars() {
+            steps.push(match c {
+                '(' => 1,
+                ')' => -1,
+                _ => {
+                    let message = format!("expected ( or ), found {:?}", c);
+                    return Err(ParseError::at_line(i + 1, message));
+                }
+            });
+        }
+    }
+    Ok(steps)
+}
+
+/// Floor the instructions end on.
+#[aoc(day = 1, part = 1)]
+pub fn floor(steps: &[i32]) -> i32 {
+    steps.iter().sum()
+}
+
+/// Position, counting from 1, of the instruction first taking Santa below
+/// the ground floor.
+#[aoc(day = 1, part = 2)]
+pub fn basement(steps: &[i32]) -> usize {
+    let mut floor = 0;
+    let below = steps.iter().position(|step| {
+        floor += step;
+        floor < 0
+    });
+    below.expect("the instructions never reach the basement") + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(line: &str) -> Vec<i32> {
+        parse(std::iter::once(String::from(line))).unwrap()
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(floor(&steps("(())")), 0);
+        assert_eq!(floor(&steps("(((")), 3);
+        assert_eq!(floor(&steps("))(((((")), 3);
+        assert_eq!(floor(&steps(")())())")), -3);
+        assert_eq!(basement(&steps(")")), 1);
+        assert_eq!(basement(&steps("()())")), 5);
+        assert!(parse(std::iter::once(String::from("(x"))).is_err());
+    }
+}